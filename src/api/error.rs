@@ -0,0 +1,181 @@
+use reqwest::StatusCode;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum BorzError {
+    // the request could not be sent or its response could not be read
+    Transport(reqwest::Error),
+    // the server answered with a non-success HTTP status
+    Status(StatusCode),
+    // the server answered with a GraphQL errors array
+    GraphQL(Vec<graphql_client::Error>),
+    // the response did not contain a field the client relies on
+    MissingData(&'static str),
+    // the stored credentials were rejected and could not be refreshed
+    AuthExpired,
+    NotLoggedIn,
+    NotFound(String),
+    InvalidInput(String),
+    // the server understood the request but refused it, such as a wrong password
+    Rejected(String),
+    InvalidUrl(url::ParseError),
+    Config(String),
+    // tokens that were refreshed and are in use could not be written to the configuration
+    Unsaved(Box<BorzError>),
+    Io(io::Error),
+}
+
+impl BorzError {
+    // django-graphql-jwt reports expired or malformed tokens as ordinary GraphQL errors;
+    // a permission denial comes with a valid token, so refreshing it would not help
+    pub fn from_graphql(errors: Vec<graphql_client::Error>) -> BorzError {
+        let messages: Vec<String> = errors.iter().map(|e| e.message.to_lowercase()).collect();
+        if messages.iter().any(|message| {
+            message.contains("signature has expired")
+                || message.contains("error decoding signature")
+        }) {
+            return BorzError::AuthExpired;
+        }
+        if let Some(pos) = messages
+            .iter()
+            .position(|message| message.contains("permission to perform this action"))
+        {
+            return BorzError::Rejected(errors[pos].message.clone());
+        }
+        BorzError::GraphQL(errors)
+    }
+
+    // turns the success flag and errors of a django-graphql-auth payload into a result
+    pub fn check(
+        success: Option<bool>,
+        errors: Option<serde_json::Value>,
+        fallback: &str,
+    ) -> Result<(), BorzError> {
+        if success.unwrap_or(false) {
+            return Ok(());
+        }
+        let mut messages = Vec::new();
+        if let Some(serde_json::Value::Object(fields)) = errors {
+            for (field, field_errors) in fields {
+                for error in field_errors.as_array().into_iter().flatten() {
+                    let message = error["message"].as_str().unwrap_or("invalid");
+                    if field == "nonFieldErrors" {
+                        messages.push(String::from(message));
+                    } else {
+                        messages.push(format!("{}: {}", field, message));
+                    }
+                }
+            }
+        }
+        if messages.is_empty() {
+            Err(BorzError::Rejected(String::from(fallback)))
+        } else {
+            Err(BorzError::Rejected(format!(
+                "{}. {}",
+                fallback,
+                messages.join("; ")
+            )))
+        }
+    }
+
+    // a stable name for the error, reported in --output json documents
+    pub fn kind(&self) -> &'static str {
+        match self {
+            BorzError::Transport(_) => "transport",
+            BorzError::Status(_) => "status",
+            BorzError::GraphQL(_) => "graphql",
+            BorzError::MissingData(_) => "missing_data",
+            BorzError::AuthExpired => "auth_expired",
+            BorzError::NotLoggedIn => "not_logged_in",
+            BorzError::NotFound(_) => "not_found",
+            BorzError::InvalidInput(_) => "invalid_input",
+            BorzError::Rejected(_) => "rejected",
+            BorzError::InvalidUrl(_) => "invalid_url",
+            BorzError::Config(_) => "config",
+            BorzError::Unsaved(_) => "unsaved",
+            BorzError::Io(_) => "io",
+        }
+    }
+
+    // scripts can tell bad input and credential problems apart from other failures
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            BorzError::InvalidInput(_) | BorzError::InvalidUrl(_) => 2,
+            BorzError::NotLoggedIn => 3,
+            BorzError::AuthExpired => 4,
+            BorzError::NotFound(_) => 5,
+            BorzError::Rejected(_) => 6,
+            _ => 1,
+        }
+    }
+}
+
+impl fmt::Display for BorzError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BorzError::Transport(e) => write!(f, "Could not reach the server: {}", e),
+            BorzError::Status(status) => write!(f, "The server responded with {}", status),
+            BorzError::GraphQL(errors) => {
+                let messages: Vec<String> = errors.iter().map(|e| e.message.clone()).collect();
+                write!(
+                    f,
+                    "The server rejected the request: {}",
+                    messages.join("; ")
+                )
+            }
+            BorzError::MissingData(field) => {
+                write!(f, "The server response is missing `{}`", field)
+            }
+            BorzError::AuthExpired => write!(
+                f,
+                "Your session has expired. Please run borz login to log in again"
+            ),
+            BorzError::NotLoggedIn => {
+                write!(f, "Please run Borz login or Borz signup to log in first")
+            }
+            BorzError::NotFound(what) => write!(f, "Could not find {}", what),
+            BorzError::InvalidInput(reason) => write!(f, "{}", reason),
+            BorzError::Rejected(reason) => write!(f, "{}", reason),
+            BorzError::InvalidUrl(e) => write!(f, "Invalid server address: {}", e),
+            BorzError::Config(reason) => write!(f, "Could not read the configuration: {}", reason),
+            BorzError::Unsaved(e) => write!(
+                f,
+                "Your session was renewed but could not be saved, so you may need to log in again next time. {}",
+                e
+            ),
+            BorzError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for BorzError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BorzError::Transport(e) => Some(e),
+            BorzError::InvalidUrl(e) => Some(e),
+            BorzError::Unsaved(e) => Some(e.as_ref()),
+            BorzError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for BorzError {
+    fn from(e: reqwest::Error) -> BorzError {
+        BorzError::Transport(e)
+    }
+}
+
+impl From<url::ParseError> for BorzError {
+    fn from(e: url::ParseError) -> BorzError {
+        BorzError::InvalidUrl(e)
+    }
+}
+
+impl From<io::Error> for BorzError {
+    fn from(e: io::Error) -> BorzError {
+        BorzError::Io(e)
+    }
+}
@@ -1,10 +1,18 @@
-use crate::CONFIG_FILE_PATH;
-use graphql_client::{GraphQLQuery, Response};
+use crate::api::error::BorzError;
+use crate::api::paginate::{Page, Paginator, PAGE_SIZE};
+use crate::api::token::TokenManager;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use graphql_client::{GraphQLQuery, QueryBody, Response};
 use reqwest::blocking::Client;
-use reqwest::header::{HeaderMap, AUTHORIZATION};
-use std::fs;
+use reqwest::header::AUTHORIZATION;
 use url::Url;
 
+// timestamps are passed through as the ISO 8601 strings the server sends
+type DateTime = String;
+// django-graphql-auth reports rejected input as a map of fields to lists of messages
+type ExpectedErrorType = serde_json::Value;
+
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "src/api/resources/schema.gql",
@@ -19,6 +27,13 @@ struct RegisterMutation;
 )]
 struct VerifyMutation;
 
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct ResendActivationEmailMutation;
+
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "src/api/resources/schema.gql",
@@ -26,6 +41,13 @@ struct VerifyMutation;
 )]
 struct AuthMutation;
 
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct RevokeTokenMutation;
+
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "src/api/resources/schema.gql",
@@ -33,6 +55,118 @@ struct AuthMutation;
 )]
 struct RefreshMutation;
 
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct SendPasswordResetEmailMutation;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct PasswordResetMutation;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct PasswordChangeMutation;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct UpdateAccountMutation;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct ArchiveAccountMutation;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct DeleteAccountMutation;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct SendSecondaryEmailActivationMutation;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct VerifySecondaryEmailMutation;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct SwapEmailsMutation;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct RemoveSecondaryEmailMutation;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct MeQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct MembershipsQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct JoinSubgroupMutation;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct LeaveSubgroupMutation;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct VerifyTokenMutation;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct SubgroupQuery;
+
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "src/api/resources/schema.gql",
@@ -40,6 +174,27 @@ struct RefreshMutation;
 )]
 struct SubgroupsQuery;
 
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct MembersQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct CreateSubgroupMutation;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct UpdateSubgroupMutation;
+
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "src/api/resources/schema.gql",
@@ -54,6 +209,27 @@ struct UserQuery;
 )]
 struct ThreadUpdateMutation;
 
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct CreateThreadMutation;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct UpdateThreadMutation;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct UpdateReplyMutation;
+
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "src/api/resources/schema.gql",
@@ -68,16 +244,53 @@ struct ThreadsQuery;
 )]
 struct ThreadContentQuery;
 
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/api/resources/schema.gql",
+    query_path = "src/api/resources/queries.gql"
+)]
+struct RepliesQuery;
+
+pub type SubgroupDetailNode = subgroup_query::SubgroupQuerySubgroup;
+pub type SubgroupNode = subgroups_query::SubgroupsQuerySubgroupChildGroupEdgesNode;
+pub type ThreadNode = threads_query::ThreadsQuerySubgroupThreadsEdgesNode;
+pub type ReplyNode = replies_query::RepliesQueryThreadRepliesEdgesNode;
+pub type MeNode = me_query::MeQueryMe;
+pub type MemberNode = members_query::MembersQuerySubgroupMembersEdgesNode;
+pub type MembershipNode = memberships_query::MembershipsQueryMeSubgroupsEdgesNode;
+
+// the default slug of a subgroup: its name lowercased with words joined by dashes
+// letters from any script are kept, so it is only empty for a name without any
+pub fn slugify(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
+}
+
+// the primary key inside a user's global id, since the signed in user is a UserNode while
+// authors are BorzUserNodes and their ids only agree on the key
+pub fn user_key(id: &str) -> Option<String> {
+    let decoded = String::from_utf8(STANDARD.decode(id).ok()?).ok()?;
+    let (_, key) = decoded.split_once(':')?;
+    Some(String::from(key))
+}
+
 #[derive(Debug, Clone)]
 pub struct APIFetcher {
     path: Url,
     client: Client,
-    token: String,
+    tokens: TokenManager,
     node_id: String,
 }
 
 impl APIFetcher {
     pub fn new(path: Url, top_id: String) -> APIFetcher {
+        APIFetcher::authenticated(path, top_id, TokenManager::default())
+    }
+
+    pub fn authenticated(path: Url, top_id: String, tokens: TokenManager) -> APIFetcher {
         let client = Client::builder()
             .user_agent("borz_client/0.1.0")
             .build()
@@ -85,28 +298,105 @@ impl APIFetcher {
         APIFetcher {
             path,
             client,
-            token: String::new(),
+            tokens,
             node_id: top_id,
         }
     }
 
-    pub fn child(&mut self, id: String) -> APIFetcher {
-        let res = self.query_subgroups();
-        for subgroup in res.data.unwrap().subgroup.unwrap().child_group.edges {
-            let node = subgroup.unwrap().node.unwrap();
-            if id == node.id {
-                return APIFetcher {
-                    path: self.path.clone(),
-                    client: Client::builder()
-                        .user_agent("borz_client/0.1.0")
-                        .build()
-                        .unwrap(),
-                    token: String::new(),
-                    node_id: id,
-                };
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    fn at(&self, id: String) -> APIFetcher {
+        APIFetcher {
+            path: self.path.clone(),
+            client: self.client.clone(),
+            tokens: self.tokens.clone(),
+            node_id: id,
+        }
+    }
+
+    // a subgroup whose id came from the server; GroupPage::new reports it missing if it was deleted
+    pub fn child(&self, id: String) -> APIFetcher {
+        self.at(id)
+    }
+
+    pub fn child_by_name(&mut self, name: &str) -> Result<APIFetcher, BorzError> {
+        let subgroups = Paginator::new().collect(|after| self.query_subgroups(after))?;
+        for node in subgroups {
+            if name == node.name {
+                return Ok(self.at(node.id));
+            }
+        }
+        Err(BorzError::NotFound(format!("subgroup {}", name)))
+    }
+
+    fn send<Q: GraphQLQuery>(
+        &self,
+        query: &QueryBody<Q::Variables>,
+        token: Option<&str>,
+    ) -> Result<Q::ResponseData, BorzError> {
+        let mut req = self.client.post(self.path.clone()).json(query);
+        if let Some(token) = token {
+            req = req.header(AUTHORIZATION, format!("JWT {}", token));
+        }
+        let res = req.send()?;
+        if !res.status().is_success() {
+            return Err(BorzError::Status(res.status()));
+        }
+        let body: Response<Q::ResponseData> = res.json()?;
+        if let Some(errors) = body.errors {
+            if !errors.is_empty() {
+                return Err(BorzError::from_graphql(errors));
+            }
+        }
+        body.data.ok_or(BorzError::MissingData("data"))
+    }
+
+    // sends the query with the cached access token, refreshing it once if the server rejects it
+    fn send_authorized<Q: GraphQLQuery>(
+        &self,
+        query: &QueryBody<Q::Variables>,
+    ) -> Result<Q::ResponseData, BorzError> {
+        let token = self.access_token(false)?;
+        match self.send::<Q>(query, Some(&token[..])) {
+            Err(BorzError::AuthExpired) => {
+                let token = self.access_token(true)?;
+                self.send::<Q>(query, Some(&token[..]))
             }
+            res => res,
+        }
+    }
+
+    fn access_token(&self, force_refresh: bool) -> Result<String, BorzError> {
+        // the lock is held during the refresh so concurrent requests only refresh once
+        let mut tokens = self.tokens.lock();
+        if !force_refresh && tokens.is_fresh() {
+            return Ok(tokens.token.clone());
+        }
+        if tokens.refresh_token.is_empty() {
+            return Err(BorzError::AuthExpired);
+        }
+        let mutation = RefreshMutation::build_query(refresh_mutation::Variables {
+            refresh_token: tokens.refresh_token.clone(),
+        });
+        let body = match self.send::<RefreshMutation>(&mutation, None) {
+            Err(BorzError::GraphQL(_)) => return Err(BorzError::AuthExpired),
+            res => res?,
+        };
+        let rt = body
+            .refresh_token
+            .ok_or(BorzError::MissingData("refreshToken"))?;
+        if !rt.success.unwrap_or(false) {
+            return Err(BorzError::AuthExpired);
         }
-        panic!("Unfound child");
+        let token = rt.token.ok_or(BorzError::MissingData("token"))?;
+        let refresh_token = rt
+            .refresh_token
+            .ok_or(BorzError::MissingData("refreshToken"))?;
+        tokens.set(token.clone(), refresh_token);
+        tokens.persist();
+        Ok(token)
     }
 
     pub fn mutate_register(
@@ -114,179 +404,505 @@ impl APIFetcher {
         email: String,
         username: String,
         password: String,
-    ) -> Response<register_mutation::ResponseData> {
+    ) -> Result<register_mutation::ResponseData, BorzError> {
         let mutation = RegisterMutation::build_query(register_mutation::Variables {
             email,
             username,
             password,
         });
-        let res = self
-            .client
-            .post(self.path.clone())
-            .json(&mutation)
-            .send()
-            .unwrap();
-        return res.json().unwrap();
+        self.send::<RegisterMutation>(&mutation, None)
     }
 
-    pub fn mutate_verify(&self, token: String) -> Response<verify_mutation::ResponseData> {
+    pub fn mutate_verify(&self, token: String) -> Result<verify_mutation::ResponseData, BorzError> {
         let mutation = VerifyMutation::build_query(verify_mutation::Variables { token });
-        let res = self
-            .client
-            .post(self.path.clone())
-            .json(&mutation)
-            .send()
-            .unwrap();
-        return res.json().unwrap();
+        self.send::<VerifyMutation>(&mutation, None)
     }
 
-    pub fn mutate_refresh(&mut self) {
-        let content = fs::read_to_string(CONFIG_FILE_PATH.clone()).unwrap();
-        let json = json::parse(&content[..]).unwrap();
-        let refresh = json["refresh_token"].to_string();
-        let username = json["username"].to_string();
-        let server = json["server"].to_string();
-        let mutation = RefreshMutation::build_query(refresh_mutation::Variables {
-            refresh_token: refresh,
+    pub fn mutate_revoke_token(&self, refresh_token: String) -> Result<(), BorzError> {
+        let mutation =
+            RevokeTokenMutation::build_query(revoke_token_mutation::Variables { refresh_token });
+        let payload = self
+            .send::<RevokeTokenMutation>(&mutation, None)?
+            .revoke_token
+            .ok_or(BorzError::MissingData("revokeToken"))?;
+        BorzError::check(
+            payload.success,
+            payload.errors,
+            "The session could not be revoked",
+        )
+    }
+
+    pub fn mutate_resend_activation_email(&self, email: String) -> Result<(), BorzError> {
+        let mutation = ResendActivationEmailMutation::build_query(
+            resend_activation_email_mutation::Variables { email },
+        );
+        let payload = self
+            .send::<ResendActivationEmailMutation>(&mutation, None)?
+            .resend_activation_email
+            .ok_or(BorzError::MissingData("resendActivationEmail"))?;
+        BorzError::check(
+            payload.success,
+            payload.errors,
+            "The activation email could not be sent",
+        )
+    }
+
+    pub fn mutate_send_password_reset_email(&self, email: String) -> Result<(), BorzError> {
+        let mutation = SendPasswordResetEmailMutation::build_query(
+            send_password_reset_email_mutation::Variables { email },
+        );
+        let payload = self
+            .send::<SendPasswordResetEmailMutation>(&mutation, None)?
+            .send_password_reset_email
+            .ok_or(BorzError::MissingData("sendPasswordResetEmail"))?;
+        BorzError::check(
+            payload.success,
+            payload.errors,
+            "The reset email could not be sent",
+        )
+    }
+
+    pub fn mutate_password_reset(&self, token: String, password: String) -> Result<(), BorzError> {
+        let mutation = PasswordResetMutation::build_query(password_reset_mutation::Variables {
+            token,
+            password,
         });
-        let res = self
-            .client
-            .post(self.path.clone())
-            .json(&mutation)
-            .send()
-            .unwrap();
-        let body: Response<refresh_mutation::ResponseData> = res.json().unwrap();
-        let body = body.data.unwrap();
-        let rt = body.refresh_token.unwrap();
-        if !rt.success.unwrap() {
-            panic!("Unsuccessful parse");
-        }
-        self.token = rt.token.unwrap();
-        let refresh_token = rt.refresh_token.unwrap();
-        fs::write(
-            CONFIG_FILE_PATH.clone(),
-            format!(
-                "{{\"token\": \"{}\", \"refresh_token\": \"{}\", \"username\": \"{}\", \"server\": \"{}\"}}",
-                self.token, refresh_token, username, server
-            ),
+        let payload = self
+            .send::<PasswordResetMutation>(&mutation, None)?
+            .password_reset
+            .ok_or(BorzError::MissingData("passwordReset"))?;
+        BorzError::check(
+            payload.success,
+            payload.errors,
+            "The password could not be reset",
+        )
+    }
+
+    // changing the password revokes the old tokens, so the new ones are stored right away
+    pub fn mutate_password_change(
+        &self,
+        old_password: String,
+        password: String,
+    ) -> Result<(), BorzError> {
+        let mutation = PasswordChangeMutation::build_query(password_change_mutation::Variables {
+            old_password,
+            password,
+        });
+        let payload = self
+            .send_authorized::<PasswordChangeMutation>(&mutation)?
+            .password_change
+            .ok_or(BorzError::MissingData("passwordChange"))?;
+        BorzError::check(
+            payload.success,
+            payload.errors,
+            "The password could not be changed",
+        )?;
+        let token = payload.token.ok_or(BorzError::MissingData("token"))?;
+        let refresh_token = payload
+            .refresh_token
+            .ok_or(BorzError::MissingData("refreshToken"))?;
+        let mut tokens = self.tokens.lock();
+        tokens.set(token, refresh_token);
+        tokens.persist();
+        Ok(())
+    }
+
+    pub fn mutate_update_account(
+        &self,
+        first_name: Option<String>,
+        last_name: Option<String>,
+    ) -> Result<(), BorzError> {
+        let mutation = UpdateAccountMutation::build_query(update_account_mutation::Variables {
+            first_name,
+            last_name,
+        });
+        let payload = self
+            .send_authorized::<UpdateAccountMutation>(&mutation)?
+            .update_account
+            .ok_or(BorzError::MissingData("updateAccount"))?;
+        BorzError::check(
+            payload.success,
+            payload.errors,
+            "The account could not be updated",
+        )
+    }
+
+    pub fn mutate_archive_account(&self, password: String) -> Result<(), BorzError> {
+        let mutation =
+            ArchiveAccountMutation::build_query(archive_account_mutation::Variables { password });
+        let payload = self
+            .send_authorized::<ArchiveAccountMutation>(&mutation)?
+            .archive_account
+            .ok_or(BorzError::MissingData("archiveAccount"))?;
+        BorzError::check(
+            payload.success,
+            payload.errors,
+            "The account could not be archived",
+        )
+    }
+
+    pub fn mutate_delete_account(&self, password: String) -> Result<(), BorzError> {
+        let mutation =
+            DeleteAccountMutation::build_query(delete_account_mutation::Variables { password });
+        let payload = self
+            .send_authorized::<DeleteAccountMutation>(&mutation)?
+            .delete_account
+            .ok_or(BorzError::MissingData("deleteAccount"))?;
+        BorzError::check(
+            payload.success,
+            payload.errors,
+            "The account could not be deleted",
         )
-        .unwrap();
     }
 
-    pub fn query_user(&mut self, uid: String) -> Response<user_query::ResponseData> {
-        self.mutate_refresh();
-        let mutation = UserQuery::build_query(user_query::Variables { id: uid });
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            AUTHORIZATION,
-            format!("JWT {}", self.token).parse().unwrap(),
+    pub fn mutate_send_secondary_email_activation(
+        &self,
+        email: String,
+        password: String,
+    ) -> Result<(), BorzError> {
+        let mutation = SendSecondaryEmailActivationMutation::build_query(
+            send_secondary_email_activation_mutation::Variables { email, password },
         );
-        let res = self
-            .client
-            .post(self.path.clone())
-            .headers(headers)
-            .json(&mutation)
-            .send()
-            .unwrap();
-        return res.json().unwrap();
+        let payload = self
+            .send_authorized::<SendSecondaryEmailActivationMutation>(&mutation)?
+            .send_secondary_email_activation
+            .ok_or(BorzError::MissingData("sendSecondaryEmailActivation"))?;
+        BorzError::check(
+            payload.success,
+            payload.errors,
+            "The email could not be added",
+        )
+    }
+
+    pub fn mutate_verify_secondary_email(&self, token: String) -> Result<(), BorzError> {
+        let mutation =
+            VerifySecondaryEmailMutation::build_query(verify_secondary_email_mutation::Variables {
+                token,
+            });
+        let payload = self
+            .send_authorized::<VerifySecondaryEmailMutation>(&mutation)?
+            .verify_secondary_email
+            .ok_or(BorzError::MissingData("verifySecondaryEmail"))?;
+        BorzError::check(
+            payload.success,
+            payload.errors,
+            "The email could not be verified",
+        )
+    }
+
+    pub fn mutate_swap_emails(&self, password: String) -> Result<(), BorzError> {
+        let mutation =
+            SwapEmailsMutation::build_query(swap_emails_mutation::Variables { password });
+        let payload = self
+            .send_authorized::<SwapEmailsMutation>(&mutation)?
+            .swap_emails
+            .ok_or(BorzError::MissingData("swapEmails"))?;
+        BorzError::check(
+            payload.success,
+            payload.errors,
+            "The emails could not be swapped",
+        )
+    }
+
+    pub fn mutate_remove_secondary_email(&self, password: String) -> Result<(), BorzError> {
+        let mutation =
+            RemoveSecondaryEmailMutation::build_query(remove_secondary_email_mutation::Variables {
+                password,
+            });
+        let payload = self
+            .send_authorized::<RemoveSecondaryEmailMutation>(&mutation)?
+            .remove_secondary_email
+            .ok_or(BorzError::MissingData("removeSecondaryEmail"))?;
+        BorzError::check(
+            payload.success,
+            payload.errors,
+            "The email could not be removed",
+        )
+    }
+
+    pub fn query_me(&self) -> Result<MeNode, BorzError> {
+        let query = MeQuery::build_query(me_query::Variables);
+        self.send_authorized::<MeQuery>(&query)?
+            .me
+            .ok_or(BorzError::AuthExpired)
+    }
+
+    // asks the server whether the stored access token is still valid, without refreshing it
+    pub fn mutate_verify_token(&self) -> Result<bool, BorzError> {
+        let token = self.tokens.lock().token.clone();
+        let mutation = VerifyTokenMutation::build_query(verify_token_mutation::Variables { token });
+        match self.send::<VerifyTokenMutation>(&mutation, None) {
+            Err(BorzError::AuthExpired) | Err(BorzError::GraphQL(_)) => Ok(false),
+            res => Ok(res?
+                .verify_token
+                .and_then(|payload| payload.success)
+                .unwrap_or(false)),
+        }
+    }
+
+    // the subgroups the logged in user is a member of
+    pub fn query_memberships(
+        &self,
+        after: Option<String>,
+    ) -> Result<Page<MembershipNode>, BorzError> {
+        let query = MembershipsQuery::build_query(memberships_query::Variables {
+            first: Some(PAGE_SIZE),
+            after,
+        });
+        let subgroups = self
+            .send_authorized::<MembershipsQuery>(&query)?
+            .me
+            .ok_or(BorzError::AuthExpired)?
+            .subgroups;
+        Page::from_edges(
+            subgroups.edges.into_iter().map(|e| e.and_then(|e| e.node)),
+            subgroups.page_info.has_next_page,
+            subgroups.page_info.end_cursor,
+        )
+    }
+
+    pub fn query_subgroup(&self) -> Result<SubgroupDetailNode, BorzError> {
+        let query = SubgroupQuery::build_query(subgroup_query::Variables {
+            id: self.node_id.clone(),
+        });
+        self.send_authorized::<SubgroupQuery>(&query)?
+            .subgroup
+            .ok_or_else(|| BorzError::NotFound(format!("subgroup {}", self.node_id)))
+    }
+
+    // creates a subgroup under this one and returns its id
+    pub fn mutate_create_subgroup(
+        &self,
+        name: String,
+        slug: String,
+        description: String,
+    ) -> Result<String, BorzError> {
+        let mutation = CreateSubgroupMutation::build_query(create_subgroup_mutation::Variables {
+            parent: self.node_id.clone(),
+            name,
+            slug,
+            description,
+        });
+        let subgroup = self
+            .send_authorized::<CreateSubgroupMutation>(&mutation)?
+            .create_subgroup
+            .and_then(|payload| payload.subgroup)
+            .ok_or(BorzError::MissingData("subgroup"))?;
+        Ok(subgroup.id)
+    }
+
+    pub fn mutate_update_subgroup(&self, description: String) -> Result<(), BorzError> {
+        let mutation = UpdateSubgroupMutation::build_query(update_subgroup_mutation::Variables {
+            id: self.node_id.clone(),
+            description,
+        });
+        self.send_authorized::<UpdateSubgroupMutation>(&mutation)?
+            .update_subgroup
+            .ok_or(BorzError::MissingData("updateSubgroup"))?;
+        Ok(())
+    }
+
+    pub fn mutate_join_subgroup(&self, id: String) -> Result<(), BorzError> {
+        let mutation = JoinSubgroupMutation::build_query(join_subgroup_mutation::Variables { id });
+        self.send_authorized::<JoinSubgroupMutation>(&mutation)?
+            .join_subgroup
+            .ok_or(BorzError::MissingData("joinSubgroup"))?;
+        Ok(())
+    }
+
+    pub fn mutate_leave_subgroup(&self, id: String) -> Result<(), BorzError> {
+        let mutation =
+            LeaveSubgroupMutation::build_query(leave_subgroup_mutation::Variables { id });
+        self.send_authorized::<LeaveSubgroupMutation>(&mutation)?
+            .leave_subgroup
+            .ok_or(BorzError::MissingData("leaveSubgroup"))?;
+        Ok(())
+    }
+
+    pub fn query_user(&mut self, uid: String) -> Result<user_query::ResponseData, BorzError> {
+        let query = UserQuery::build_query(user_query::Variables { id: uid });
+        self.send_authorized::<UserQuery>(&query)
     }
 
     pub fn query_thread_content(
         &mut self,
         tid: String,
-    ) -> Response<thread_content_query::ResponseData> {
-        self.mutate_refresh();
-        let mutation = ThreadContentQuery::build_query(thread_content_query::Variables { id: tid });
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            AUTHORIZATION,
-            format!("JWT {}", self.token).parse().unwrap(),
-        );
-        let res = self
-            .client
-            .post(self.path.clone())
-            .headers(headers)
-            .json(&mutation)
-            .send()
-            .unwrap();
-        return res.json().unwrap();
+    ) -> Result<thread_content_query::ResponseData, BorzError> {
+        let query = ThreadContentQuery::build_query(thread_content_query::Variables { id: tid });
+        self.send_authorized::<ThreadContentQuery>(&query)
+    }
+
+    pub fn query_replies(
+        &mut self,
+        tid: String,
+        after: Option<String>,
+    ) -> Result<Page<ReplyNode>, BorzError> {
+        let query = RepliesQuery::build_query(replies_query::Variables {
+            id: tid.clone(),
+            first: Some(PAGE_SIZE),
+            after,
+        });
+        let replies = self
+            .send_authorized::<RepliesQuery>(&query)?
+            .thread
+            .ok_or_else(|| BorzError::NotFound(format!("thread {}", tid)))?
+            .replies;
+        Page::from_edges(
+            replies.edges.into_iter().map(|e| e.and_then(|e| e.node)),
+            replies.page_info.has_next_page,
+            replies.page_info.end_cursor,
+        )
     }
 
-    pub fn query_threads(&mut self) -> Response<threads_query::ResponseData> {
-        self.mutate_refresh();
-        let mutation = ThreadsQuery::build_query(threads_query::Variables {
+    pub fn query_threads(&mut self, after: Option<String>) -> Result<Page<ThreadNode>, BorzError> {
+        let query = ThreadsQuery::build_query(threads_query::Variables {
             id: self.node_id.clone(),
+            first: Some(PAGE_SIZE),
+            after,
         });
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            AUTHORIZATION,
-            format!("JWT {}", self.token).parse().unwrap(),
-        );
-        let res = self
-            .client
-            .post(self.path.clone())
-            .headers(headers)
-            .json(&mutation)
-            .send()
-            .unwrap();
-        return res.json().unwrap();
+        let threads = self
+            .send_authorized::<ThreadsQuery>(&query)?
+            .subgroup
+            .ok_or(BorzError::MissingData("subgroup"))?
+            .threads;
+        Page::from_edges(
+            threads.edges.into_iter().map(|e| e.and_then(|e| e.node)),
+            threads.page_info.has_next_page,
+            threads.page_info.end_cursor,
+        )
     }
 
-    pub fn query_subgroups(&mut self) -> Response<subgroups_query::ResponseData> {
-        self.mutate_refresh();
-        let mutation = SubgroupsQuery::build_query(subgroups_query::Variables {
+    pub fn query_subgroups(
+        &mut self,
+        after: Option<String>,
+    ) -> Result<Page<SubgroupNode>, BorzError> {
+        let query = SubgroupsQuery::build_query(subgroups_query::Variables {
             id: self.node_id.clone(),
+            first: Some(PAGE_SIZE),
+            after,
         });
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            AUTHORIZATION,
-            format!("JWT {}", self.token).parse().unwrap(),
-        );
-        let res = self
-            .client
-            .post(self.path.clone())
-            .headers(headers)
-            .json(&mutation)
-            .send()
-            .unwrap();
-        return res.json().unwrap();
+        let child_group = self
+            .send_authorized::<SubgroupsQuery>(&query)?
+            .subgroup
+            .ok_or(BorzError::MissingData("subgroup"))?
+            .child_group;
+        Page::from_edges(
+            child_group
+                .edges
+                .into_iter()
+                .map(|e| e.and_then(|e| e.node)),
+            child_group.page_info.has_next_page,
+            child_group.page_info.end_cursor,
+        )
+    }
+
+    // members of this subgroup whose username contains the filter
+    pub fn query_members(
+        &self,
+        username: Option<String>,
+        after: Option<String>,
+    ) -> Result<Page<MemberNode>, BorzError> {
+        let query = MembersQuery::build_query(members_query::Variables {
+            id: self.node_id.clone(),
+            first: Some(PAGE_SIZE),
+            after,
+            username,
+        });
+        let members = self
+            .send_authorized::<MembersQuery>(&query)?
+            .subgroup
+            .ok_or(BorzError::MissingData("subgroup"))?
+            .members;
+        Page::from_edges(
+            members.edges.into_iter().map(|e| e.and_then(|e| e.node)),
+            members.page_info.has_next_page,
+            members.page_info.end_cursor,
+        )
     }
 
     pub fn mutate_thread_reply(
         &mut self,
         id: String,
         content: String,
-    ) -> Response<thread_update_mutation::ResponseData> {
-        self.mutate_refresh();
+    ) -> Result<thread_update_mutation::ResponseData, BorzError> {
         let mutation =
             ThreadUpdateMutation::build_query(thread_update_mutation::Variables { id, content });
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            AUTHORIZATION,
-            format!("JWT {}", self.token).parse().unwrap(),
-        );
-        let res = self
-            .client
-            .post(self.path.clone())
-            .headers(headers)
-            .json(&mutation)
-            .send()
-            .unwrap();
-        return res.json().unwrap();
+        self.send_authorized::<ThreadUpdateMutation>(&mutation)
+    }
+
+    pub fn mutate_create_thread(
+        &mut self,
+        title: String,
+        content: String,
+    ) -> Result<create_thread_mutation::ResponseData, BorzError> {
+        let mutation = CreateThreadMutation::build_query(create_thread_mutation::Variables {
+            subgroup: self.node_id.clone(),
+            title,
+            content,
+        });
+        self.send_authorized::<CreateThreadMutation>(&mutation)
+    }
+
+    pub fn mutate_update_thread(
+        &mut self,
+        id: String,
+        title: String,
+        content: String,
+    ) -> Result<update_thread_mutation::ResponseData, BorzError> {
+        let mutation = UpdateThreadMutation::build_query(update_thread_mutation::Variables {
+            id,
+            title,
+            content,
+        });
+        self.send_authorized::<UpdateThreadMutation>(&mutation)
+    }
+
+    pub fn mutate_update_reply(
+        &mut self,
+        id: String,
+        content: String,
+    ) -> Result<update_reply_mutation::ResponseData, BorzError> {
+        let mutation =
+            UpdateReplyMutation::build_query(update_reply_mutation::Variables { id, content });
+        self.send_authorized::<UpdateReplyMutation>(&mutation)
     }
 
     pub fn mutate_auth(
         &mut self,
         username: String,
         password: String,
-    ) -> Response<auth_mutation::ResponseData> {
+    ) -> Result<auth_mutation::ResponseData, BorzError> {
         let mutation = AuthMutation::build_query(auth_mutation::Variables { username, password });
-        let res = self
-            .client
-            .post(self.path.clone())
-            .json(&mutation)
-            .send()
-            .unwrap();
-        return res.json().unwrap();
+        self.send::<AuthMutation>(&mutation, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_a_user_across_node_types() {
+        // UserNode:5, BorzUserNode:5 and BorzUserNode:15
+        let me = user_key("VXNlck5vZGU6NQ==");
+        assert_eq!(me, Some(String::from("5")));
+        assert_eq!(user_key("Qm9yelVzZXJOb2RlOjU="), me);
+        assert_ne!(user_key("Qm9yelVzZXJOb2RlOjE1"), me);
+    }
+
+    #[test]
+    fn has_no_key_for_a_malformed_id() {
+        assert_eq!(user_key(""), None);
+        assert_eq!(user_key("not base64!"), None);
+        // "UserNode" without a key
+        assert_eq!(user_key("VXNlck5vZGU="), None);
+    }
+
+    #[test]
+    fn slugifies_names_in_any_script() {
+        assert_eq!(slugify("Quantum Physics"), "quantum-physics");
+        assert_eq!(slugify("Économie & Société"), "économie-société");
+        assert_eq!(slugify("物理"), "物理");
+        assert_eq!(slugify("!!!"), "");
     }
 }
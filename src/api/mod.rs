@@ -1 +1,4 @@
+pub mod error;
 pub mod fetch;
+pub mod paginate;
+pub mod token;
@@ -0,0 +1,189 @@
+use crate::api::error::BorzError;
+
+// number of nodes requested from a connection at a time
+pub const PAGE_SIZE: i64 = 20;
+// how close a selection may get to the end of a loaded list before the next page is fetched
+pub const LOAD_AHEAD: usize = 5;
+
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub nodes: Vec<T>,
+    pub has_next_page: bool,
+    pub end_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    // builds a page out of the edges of a Relay-style connection
+    pub fn from_edges<I>(
+        edges: I,
+        has_next_page: bool,
+        end_cursor: Option<String>,
+    ) -> Result<Page<T>, BorzError>
+    where
+        I: IntoIterator<Item = Option<T>>,
+    {
+        let nodes = edges
+            .into_iter()
+            .map(|node| node.ok_or(BorzError::MissingData("node")))
+            .collect::<Result<Vec<T>, BorzError>>()?;
+        Ok(Page {
+            nodes,
+            has_next_page,
+            end_cursor,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Paginator {
+    after: Option<String>,
+    has_next_page: bool,
+}
+
+impl Paginator {
+    pub fn new() -> Paginator {
+        Paginator {
+            after: None,
+            has_next_page: true,
+        }
+    }
+
+    // resumes a connection after a cursor handed out by an earlier walk
+    pub fn starting_at(after: Option<String>) -> Paginator {
+        Paginator {
+            after,
+            has_next_page: true,
+        }
+    }
+
+    pub fn has_next_page(&self) -> bool {
+        self.has_next_page
+    }
+
+    pub fn end_cursor(&self) -> Option<String> {
+        self.after.clone()
+    }
+
+    // fetches the page after the last one seen, or nothing once the connection is exhausted
+    pub fn next<T, F>(&mut self, fetch: F) -> Result<Vec<T>, BorzError>
+    where
+        F: FnOnce(Option<String>) -> Result<Page<T>, BorzError>,
+    {
+        if !self.has_next_page {
+            return Ok(Vec::new());
+        }
+        let page = fetch(self.after.clone())?;
+        self.has_next_page = page.has_next_page && page.end_cursor.is_some();
+        // an empty last page has no cursor, so keep the one that led to it
+        if page.end_cursor.is_some() {
+            self.after = page.end_cursor;
+        }
+        Ok(page.nodes)
+    }
+
+    // walks every remaining page of the connection
+    pub fn collect<T, F>(&mut self, mut fetch: F) -> Result<Vec<T>, BorzError>
+    where
+        F: FnMut(Option<String>) -> Result<Page<T>, BorzError>,
+    {
+        let mut nodes = Vec::new();
+        while self.has_next_page {
+            nodes.append(&mut self.next(&mut fetch)?);
+        }
+        Ok(nodes)
+    }
+}
+
+impl Default for Paginator {
+    fn default() -> Paginator {
+        Paginator::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a page of the given nodes, ending at a cursor named after its last node
+    fn page(nodes: &[u32], has_next_page: bool) -> Page<u32> {
+        Page {
+            nodes: nodes.to_vec(),
+            has_next_page,
+            end_cursor: nodes.last().map(|node| format!("c{}", node)),
+        }
+    }
+
+    #[test]
+    fn follows_the_end_cursor_while_there_are_more_pages() {
+        let mut paginator = Paginator::new();
+        let mut seen = vec![];
+        let nodes = paginator
+            .collect(|after| {
+                seen.push(after.clone());
+                Ok(match after.as_deref() {
+                    None => page(&[1, 2], true),
+                    Some("c2") => page(&[3, 4], true),
+                    Some("c4") => page(&[5], false),
+                    other => panic!("unexpected cursor {:?}", other),
+                })
+            })
+            .unwrap();
+        assert_eq!(nodes, vec![1, 2, 3, 4, 5]);
+        assert_eq!(
+            seen,
+            vec![None, Some(String::from("c2")), Some(String::from("c4"))]
+        );
+        assert!(!paginator.has_next_page());
+        assert_eq!(paginator.end_cursor().as_deref(), Some("c5"));
+    }
+
+    #[test]
+    fn stops_on_an_empty_last_page_and_keeps_the_cursor() {
+        let mut paginator = Paginator::new();
+        assert_eq!(
+            paginator.next(|_| Ok(page(&[1, 2], true))).unwrap(),
+            vec![1, 2]
+        );
+        let empty: Page<u32> = Page {
+            nodes: vec![],
+            has_next_page: true,
+            end_cursor: None,
+        };
+        assert!(paginator.next(|_| Ok(empty)).unwrap().is_empty());
+        assert!(!paginator.has_next_page());
+        assert_eq!(paginator.end_cursor().as_deref(), Some("c2"));
+        let nodes: Vec<u32> = paginator
+            .next(|_| panic!("an exhausted connection is not fetched"))
+            .unwrap();
+        assert!(nodes.is_empty());
+    }
+
+    #[test]
+    fn resumes_after_a_cursor() {
+        let mut paginator = Paginator::starting_at(Some(String::from("c4")));
+        let nodes = paginator
+            .collect(|after| {
+                assert_eq!(after.as_deref(), Some("c4"));
+                Ok(page(&[5, 6], false))
+            })
+            .unwrap();
+        assert_eq!(nodes, vec![5, 6]);
+        assert_eq!(paginator.end_cursor().as_deref(), Some("c6"));
+    }
+
+    #[test]
+    fn keeps_its_place_when_a_page_fails() {
+        let mut paginator = Paginator::new();
+        paginator.next(|_| Ok(page(&[1], true))).unwrap();
+        let res = paginator.next(|_| Err::<Page<u32>, _>(BorzError::MissingData("node")));
+        assert!(res.is_err());
+        assert!(paginator.has_next_page());
+        assert_eq!(paginator.end_cursor().as_deref(), Some("c1"));
+    }
+
+    #[test]
+    fn rejects_a_missing_node() {
+        let page = Page::from_edges(vec![Some(1), None], false, None);
+        assert!(matches!(page, Err(BorzError::MissingData("node"))));
+    }
+}
@@ -0,0 +1,169 @@
+use crate::api::error::BorzError;
+use crate::config::Config;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// refresh tokens this many seconds before they actually expire
+const EXPIRY_MARGIN: u64 = 30;
+
+#[derive(Debug, Default)]
+pub struct Tokens {
+    // the config profile the tokens are saved back to
+    profile: String,
+    pub token: String,
+    pub refresh_token: String,
+    expires_at: Option<u64>,
+    // set by the interface, which shows a failure to save the tokens instead of printing it
+    keep_unsaved: bool,
+    unsaved: Option<BorzError>,
+}
+
+// reads the exp claim out of a JWT without verifying its signature
+fn decode_expiry(token: &str) -> Option<u64> {
+    let payload = token.split('.').nth(1)?;
+    let bytes = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    let claims = json::parse(&String::from_utf8(bytes).ok()?[..]).ok()?;
+    claims["exp"].as_u64()
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl Tokens {
+    // a token without a readable expiry is trusted until the server rejects it
+    pub fn is_fresh(&self) -> bool {
+        !self.token.is_empty()
+            && self
+                .expires_at
+                .is_none_or(|exp| now() + EXPIRY_MARGIN < exp)
+    }
+
+    pub fn set(&mut self, token: String, refresh_token: String) {
+        self.expires_at = decode_expiry(&token);
+        self.token = token;
+        self.refresh_token = refresh_token;
+    }
+
+    // stores rotated tokens in the profile they were loaded from; they are already in use,
+    // so failing to save them only means the next run starts from the old ones
+    pub fn persist(&mut self) {
+        // tokens from the environment belong to no profile
+        if self.profile.is_empty() {
+            return;
+        }
+        let res = Config::update(|config| {
+            // a profile removed while the client was running stays removed
+            match config.profiles.get_mut(&self.profile) {
+                Some(profile) => {
+                    profile.token = self.token.clone();
+                    profile.refresh_token = self.refresh_token.clone();
+                    true
+                }
+                None => false,
+            }
+        });
+        if let Err(e) = res {
+            let e = BorzError::Unsaved(Box::new(e));
+            if self.keep_unsaved {
+                self.unsaved = Some(e);
+            } else {
+                eprintln!("{}", e);
+            }
+        }
+    }
+}
+
+// shared between every clone of a fetcher so a refresh is only done once
+#[derive(Debug, Clone, Default)]
+pub struct TokenManager {
+    tokens: Arc<Mutex<Tokens>>,
+}
+
+impl TokenManager {
+    pub fn new(profile: String, token: String, refresh_token: String) -> TokenManager {
+        let mut tokens = Tokens {
+            profile,
+            ..Tokens::default()
+        };
+        tokens.set(token, refresh_token);
+        TokenManager {
+            tokens: Arc::new(Mutex::new(tokens)),
+        }
+    }
+
+    // has a failure to save the tokens wait for take_unsaved rather than go to stderr
+    pub fn keep_unsaved(&self) {
+        self.lock().keep_unsaved = true;
+    }
+
+    pub fn take_unsaved(&self) -> Option<BorzError> {
+        self.lock().unsaved.take()
+    }
+
+    pub fn lock(&self) -> MutexGuard<'_, Tokens> {
+        // a panic while refreshing leaves the tokens as they were, so a poisoned lock is still usable
+        self.tokens
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a token whose payload holds the given claims, with a signature that is never checked
+    fn token_with(claims: &str) -> String {
+        format!("e30.{}.signature", URL_SAFE_NO_PAD.encode(claims))
+    }
+
+    fn tokens_expiring_at(expires_at: Option<u64>) -> Tokens {
+        Tokens {
+            token: String::from("token"),
+            expires_at,
+            ..Tokens::default()
+        }
+    }
+
+    #[test]
+    fn decodes_the_expiry_claim() {
+        assert_eq!(
+            decode_expiry(&token_with(r#"{"username":"alice","exp":1700000000}"#)),
+            Some(1700000000)
+        );
+    }
+
+    #[test]
+    fn has_no_expiry_for_an_unreadable_token() {
+        assert_eq!(decode_expiry(""), None);
+        assert_eq!(decode_expiry("not a jwt"), None);
+        assert_eq!(decode_expiry("e30.!!!.signature"), None);
+        assert_eq!(decode_expiry(&token_with(r#"{"username":"alice"}"#)), None);
+    }
+
+    #[test]
+    fn is_fresh_until_the_margin_before_expiry() {
+        assert!(tokens_expiring_at(Some(now() + EXPIRY_MARGIN + 60)).is_fresh());
+        assert!(!tokens_expiring_at(Some(now() + EXPIRY_MARGIN - 1)).is_fresh());
+        assert!(!tokens_expiring_at(Some(now() - 60)).is_fresh());
+    }
+
+    #[test]
+    fn trusts_a_token_without_an_expiry() {
+        assert!(tokens_expiring_at(None).is_fresh());
+        assert!(!Tokens::default().is_fresh());
+    }
+
+    #[test]
+    fn reads_the_expiry_when_tokens_are_set() {
+        let mut tokens = Tokens::default();
+        tokens.set(token_with(r#"{"exp":42}"#), String::from("refresh"));
+        assert_eq!(tokens.expires_at, Some(42));
+    }
+}
@@ -1,81 +1,214 @@
+use crate::api::error::BorzError;
 use crate::api::fetch::APIFetcher;
-use crate::widgets::page::{GroupPage, ThreadPage, UserPage};
+use crate::api::token::TokenManager;
+use crate::config::Profile;
+use crate::widgets::page::{GroupPage, LoadingPage, ThreadPage, UserPage};
 use crate::TOP_LEVEL_ID;
-use clap::ArgMatches;
+use crossbeam_channel::{unbounded, Receiver, Sender};
 use crossterm::event::KeyEvent;
-use json::JsonValue;
+use std::thread;
 use url::Url;
 
+// applied to the app on the main thread once a background job is done
+pub type Update = Box<dyn FnOnce(&mut App) + Send>;
+// runs on a worker thread so the interface keeps responding while it waits on the network
+pub type Job = Box<dyn FnOnce() -> Update + Send>;
+
 #[derive(Debug)]
 pub enum AppPage {
     User(UserPage),
-    Group(GroupPage),
-    Thread(ThreadPage),
+    Group(Box<GroupPage>),
+    Thread(Box<ThreadPage>),
+    Loading(LoadingPage),
 }
 
 #[derive(Debug)]
-pub struct App<'a> {
+pub struct App {
     route: Vec<AppPage>,
-    args: ArgMatches<'a>,
-    config: JsonValue,
+    profile: Profile,
+    tokens: TokenManager,
+    // kept whole so a failed first page exits with the error's own code
+    error: Option<BorzError>,
+    next_load_id: usize,
+    sender: Sender<Update>,
+    receiver: Receiver<Update>,
 }
 
-impl<'a> App<'a> {
-    pub fn new(args: ArgMatches<'a>, config: JsonValue) -> App {
+impl App {
+    pub fn new(profile: Profile) -> App {
+        let (sender, receiver) = unbounded();
         App {
             route: Vec::new(),
-            args,
-            config,
+            profile,
+            tokens: TokenManager::default(),
+            error: None,
+            next_load_id: 0,
+            sender,
+            receiver,
         }
     }
 
-    pub fn start(&mut self) {
-        self.route.push(AppPage::Group(GroupPage::new(
-            APIFetcher::new(
-                Url::parse(&self.config["server"].to_string()[..]).unwrap(),
-                TOP_LEVEL_ID.clone(),
-            ),
-            String::from("/Universe"),
-            self.config["username"].to_string(),
-        )));
+    pub fn start(&mut self) -> Result<(), BorzError> {
+        self.tokens = TokenManager::new(
+            self.profile.name.clone(),
+            self.profile.token.clone(),
+            self.profile.refresh_token.clone(),
+        );
+        // printing would draw over the interface, so failures to save tokens become popups
+        self.tokens.keep_unsaved();
+        let fetcher = APIFetcher::authenticated(
+            Url::parse(&self.profile.server)?,
+            TOP_LEVEL_ID.clone(),
+            self.tokens.clone(),
+        );
+        let username = self.profile.username.clone();
+        self.load_page(String::from("/Universe"), false, move || {
+            GroupPage::new(fetcher, String::from("/Universe"), username)
+                .map(|gp| AppPage::Group(Box::new(gp)))
+        });
+        Ok(())
     }
 
     pub fn tick(&mut self) {
-        match self.get_page().unwrap() {
-            _ => {}
+        if let Some(AppPage::Loading(lp)) = self.get_page() {
+            lp.tick();
         }
     }
 
+    #[allow(clippy::needless_return)]
     pub fn update(&mut self, chr: KeyEvent) -> bool {
         if self.route.is_empty() {
             return false;
         }
+        // any key dismisses the error popup
+        if self.error.is_some() {
+            self.error = None;
+            return true;
+        }
         let closure = match self.get_page().unwrap() {
             AppPage::Group(gp) => gp.update(chr),
             AppPage::User(up) => up.update(chr),
             AppPage::Thread(tp) => tp.update(chr),
+            AppPage::Loading(lp) => lp.update(chr),
         };
         closure(self);
         return !self.route.is_empty();
     }
 
+    pub fn paste(&mut self, text: String) {
+        if self.error.is_some() {
+            return;
+        }
+        match self.get_page() {
+            Some(AppPage::Group(gp)) => gp.paste(&text),
+            Some(AppPage::Thread(tp)) => tp.paste(&text),
+            _ => {}
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.route.is_empty()
+    }
+
+    pub fn updates(&self) -> Receiver<Update> {
+        self.receiver.clone()
+    }
+
+    pub fn spawn(&self, job: Job) {
+        let sender = self.sender.clone();
+        let tokens = self.tokens.clone();
+        thread::spawn(move || {
+            let update = job();
+            let unsaved = tokens.take_unsaved();
+            // the receiver only goes away when the app is shutting down
+            let _ = sender.send(Box::new(move |app: &mut App| {
+                update(app);
+                // an error from the job itself matters more than the tokens not being saved
+                if let Some(e) = unsaved {
+                    if app.error.is_none() {
+                        app.show_error(e);
+                    }
+                }
+            }));
+        });
+    }
+
+    // shows a placeholder while the page is built on a worker thread
+    // if replace is set, the page below the placeholder is swapped out once loading succeeds
+    pub fn load_page<F>(&mut self, title: String, replace: bool, load: F)
+    where
+        F: FnOnce() -> Result<AppPage, BorzError> + Send + 'static,
+    {
+        let id = self.next_load_id;
+        self.next_load_id += 1;
+        self.push_page(AppPage::Loading(LoadingPage::new(id, title, replace)));
+        self.spawn(Box::new(move || {
+            let res = load();
+            Box::new(move |app: &mut App| app.finish_loading(id, res))
+        }));
+    }
+
+    fn finish_loading(&mut self, id: usize, res: Result<AppPage, BorzError>) {
+        // the placeholder is gone if the user left the page before it loaded
+        let pos = self.route.iter().position(|page| match page {
+            AppPage::Loading(lp) => lp.id == id,
+            _ => false,
+        });
+        if let Some(pos) = pos {
+            let replace = match &self.route[pos] {
+                AppPage::Loading(lp) => lp.replace,
+                _ => false,
+            };
+            match res {
+                Ok(page) => {
+                    self.route[pos] = page;
+                    if replace && pos > 0 {
+                        self.route.remove(pos - 1);
+                    }
+                }
+                Err(e) => {
+                    self.route.remove(pos);
+                    self.show_error(e);
+                }
+            }
+        }
+    }
+
     pub fn push_page(&mut self, page: AppPage) {
         self.route.push(page);
     }
 
     pub fn pop_page(&mut self) -> Option<AppPage> {
-        if self.route.is_empty() {
-            None
-        } else {
-            Some(self.route.pop().unwrap())
-        }
+        self.route.pop()
     }
 
     pub fn get_page(&mut self) -> Option<&mut AppPage> {
-        if self.route.is_empty() {
-            None
-        } else {
-            Some(self.route.last_mut().unwrap())
-        }
+        self.route.last_mut()
+    }
+
+    pub fn find_group(&mut self, group_path: &str) -> Option<&mut GroupPage> {
+        self.route.iter_mut().rev().find_map(|page| match page {
+            AppPage::Group(gp) if gp.group_path == group_path => Some(gp.as_mut()),
+            _ => None,
+        })
+    }
+
+    pub fn find_thread(&mut self, thread_id: &str) -> Option<&mut ThreadPage> {
+        self.route.iter_mut().rev().find_map(|page| match page {
+            AppPage::Thread(tp) if tp.thread_id == thread_id => Some(tp.as_mut()),
+            _ => None,
+        })
+    }
+
+    pub fn show_error(&mut self, error: BorzError) {
+        self.error = Some(error);
+    }
+
+    pub fn get_error(&self) -> Option<String> {
+        self.error.as_ref().map(|e| e.to_string())
+    }
+
+    pub fn take_error(&mut self) -> Option<BorzError> {
+        self.error.take()
     }
 }
@@ -1,17 +1,329 @@
-use clap::{App, ArgMatches, SubCommand};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+
+fn yes_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("yes")
+        .help("Skips the confirmation prompt")
+        .short("y")
+        .long("yes")
+}
+
+fn group_path_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("PATH")
+        .help("The path of the group, such as /Universe/Science")
+        .required(true)
+        .index(1)
+}
 
 pub fn parse_args<'a>() -> ArgMatches<'a> {
     App::new("Borz")
         .version("0.1")
         .about("A command line interface for the Borz social networking platform")
+        .arg(
+            Arg::with_name("output")
+                .help("Prints results as prose for people or as JSON documents for scripts")
+                .long("output")
+                .takes_value(true)
+                .possible_values(&["text", "json"])
+                .default_value("text")
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("profile")
+                .help("Uses the credentials of a named profile instead of the current one")
+                .long("profile")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("server")
+                .help("The address of the server, instead of the one stored in the profile")
+                .long("server")
+                .env("BORZ_SERVER")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("username")
+                .help("The username to log in or sign up with instead of asking for it")
+                .long("username")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("password-stdin")
+                .help("Reads the password from the first line of stdin instead of the terminal")
+                .long("password-stdin")
+                .global(true),
+        )
         .subcommand(
             SubCommand::with_name("clean").about("Deletes all configuration and cache files"),
         )
         .subcommand(SubCommand::with_name("login").about("Logs in to your Borz account"))
-        .subcommand(SubCommand::with_name("logout").about("Logs out of your account"))
-        .subcommand(SubCommand::with_name("signup"))
-        .about("Creates a new Borz account")
-        .subcommand(SubCommand::with_name("verify"))
-        .about("Verifies the email of a new account")
+        .subcommand(
+            SubCommand::with_name("logout")
+                .about("Logs out of your account and revokes its session on the server")
+                .arg(
+                    Arg::with_name("all-profiles")
+                        .help("Logs out of every profile instead of the current one")
+                        .long("all-profiles"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("signup")
+                .about("Creates a new Borz account")
+                .arg(
+                    Arg::with_name("email")
+                        .help("The email to sign up with instead of asking for it")
+                        .long("email")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("verify")
+                .about("Verifies the email of a new account")
+                .arg(
+                    Arg::with_name("KEY")
+                        .help("The key from the activation email instead of asking for it")
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("resend")
+                        .help("Sends the activation email to an address again")
+                        .long("resend")
+                        .value_name("EMAIL")
+                        .takes_value(true)
+                        .conflicts_with("KEY"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("whoami")
+                .about("Checks that you are still logged in and prints your account"),
+        )
+        .subcommand(
+            SubCommand::with_name("ls")
+                .about("Lists the subgroups and threads of a group")
+                .arg(
+                    Arg::with_name("PATH")
+                        .help("The path of the group, such as /Universe/Science")
+                        .index(1),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("cat")
+                .about("Prints a thread and all of its replies")
+                .arg(
+                    Arg::with_name("THREAD")
+                        .help("The id of the thread or its path, such as /Universe/Science/Title")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("after")
+                        .help("Only prints the replies after a cursor from an earlier JSON output")
+                        .long("after")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("group")
+                .about("Joins, leaves and manages subgroups")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("join")
+                        .about("Becomes a member of a subgroup")
+                        .arg(group_path_arg()),
+                )
+                .subcommand(
+                    SubCommand::with_name("leave")
+                        .about("Stops being a member of a subgroup")
+                        .arg(group_path_arg()),
+                )
+                .subcommand(
+                    SubCommand::with_name("create")
+                        .about("Creates a subgroup, whose name is the last part of the path")
+                        .arg(group_path_arg())
+                        .arg(
+                            Arg::with_name("slug")
+                                .help("The slug of the subgroup, derived from its name by default")
+                                .long("slug")
+                                .takes_value(true),
+                        )
+                        .arg(
+                            Arg::with_name("description")
+                                .help("The description of the subgroup")
+                                .long("description")
+                                .takes_value(true),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("describe")
+                        .about("Prints the description of a subgroup, or changes it with --set")
+                        .arg(group_path_arg())
+                        .arg(
+                            Arg::with_name("set")
+                                .help("Reads a new description from stdin, a file or $EDITOR; an empty one clears it")
+                                .long("set"),
+                        )
+                        .arg(
+                            Arg::with_name("file")
+                                .help("Reads the new description from a file")
+                                .short("f")
+                                .long("file")
+                                .takes_value(true)
+                                .requires("set"),
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("post")
+                .about("Creates a thread, reading its content from stdin, a file or $EDITOR")
+                .arg(
+                    Arg::with_name("GROUP")
+                        .help("The path of the group to post in, such as /Universe/Science")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("title")
+                        .help("The title of the thread")
+                        .short("t")
+                        .long("title")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("file")
+                        .help("Reads the content of the thread from a file")
+                        .short("f")
+                        .long("file")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("reply")
+                .about("Replies to a thread, reading the reply from stdin, a file or $EDITOR")
+                .arg(
+                    Arg::with_name("THREAD")
+                        .help("The id of the thread or its path, such as /Universe/Science/Title")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("file")
+                        .help("Reads the reply from a file")
+                        .short("f")
+                        .long("file")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("account")
+                .about("Changes your name, or archives or deletes your account")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("name")
+                        .about("Sets the first and last name shown on your account")
+                        .arg(
+                            Arg::with_name("first")
+                                .help("The new first name")
+                                .long("first")
+                                .takes_value(true),
+                        )
+                        .arg(
+                            Arg::with_name("last")
+                                .help("The new last name")
+                                .long("last")
+                                .takes_value(true),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("archive")
+                        .about("Hides your account until you log in again")
+                        .arg(yes_arg()),
+                )
+                .subcommand(
+                    SubCommand::with_name("delete")
+                        .about("Permanently deletes your account and logs you out")
+                        .arg(yes_arg()),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("email")
+                .about("Manages the secondary email of your account")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("show").about("Prints your primary and secondary emails"),
+                )
+                .subcommand(
+                    SubCommand::with_name("add")
+                        .about("Sends a token to a new secondary email")
+                        .arg(
+                            Arg::with_name("EMAIL")
+                                .help("The address to add")
+                                .required(true)
+                                .index(1),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("verify")
+                        .about("Verifies the secondary email with the token sent to it")
+                        .arg(
+                            Arg::with_name("TOKEN")
+                                .help("The token from the email instead of asking for it")
+                                .index(1),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("swap").about(
+                        "Makes the secondary email the primary one and the other way around",
+                    ),
+                )
+                .subcommand(SubCommand::with_name("remove").about("Removes the secondary email")),
+        )
+        .subcommand(
+            SubCommand::with_name("password")
+                .about("Resets a forgotten password or changes the current one")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("forgot")
+                        .about("Sends an email with a token to reset your password")
+                        .arg(
+                            Arg::with_name("EMAIL")
+                                .help("The email of the account instead of asking for it")
+                                .index(1),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("reset")
+                        .about("Sets a new password using the token from the reset email")
+                        .arg(
+                            Arg::with_name("TOKEN")
+                                .help("The token from the reset email instead of asking for it")
+                                .index(1),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("change")
+                        .about("Changes the password of the account you are logged in to"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("profile")
+                .about("Manages the accounts that Borz is logged in to")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("list").about("Lists the profiles that are logged in"),
+                )
+                .subcommand(
+                    SubCommand::with_name("use")
+                        .about("Makes a profile the one used when --profile is not given")
+                        .arg(Arg::with_name("NAME").required(true).index(1)),
+                )
+                .subcommand(
+                    SubCommand::with_name("remove")
+                        .about("Forgets the credentials of a profile")
+                        .arg(Arg::with_name("NAME").required(true).index(1)),
+                ),
+        )
         .get_matches()
 }
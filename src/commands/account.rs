@@ -0,0 +1,68 @@
+use crate::api::error::BorzError;
+use crate::commands::{confirm, fetcher_from_profile, read_secret};
+use crate::config::{Config, Overrides, Profile};
+use crate::output::Output;
+use clap::ArgMatches;
+use json::JsonValue;
+
+// renames, archives or deletes the account of the profile
+pub fn account(
+    config: &mut Config,
+    profile: Option<&Profile>,
+    args: &ArgMatches,
+    overrides: &Overrides,
+    out: Output,
+) -> Result<(), BorzError> {
+    let fetcher = fetcher_from_profile(profile)?;
+    match args.subcommand() {
+        ("name", Some(args)) => {
+            let first_name = args.value_of("first").map(String::from);
+            let last_name = args.value_of("last").map(String::from);
+            if first_name.is_none() && last_name.is_none() {
+                return Err(BorzError::InvalidInput(String::from(
+                    "Please give a --first or --last name to change",
+                )));
+            }
+            fetcher.mutate_update_account(first_name.clone(), last_name.clone())?;
+            let mut document = JsonValue::new_object();
+            document["first_name"] = first_name.into();
+            document["last_name"] = last_name.into();
+            out.success(document, "Your name has been updated.");
+        }
+        ("archive", Some(args)) => {
+            confirm(
+                args,
+                "Archiving hides your account until you log in again. Continue?",
+            )?;
+            let password = read_secret(overrides, "Enter your password:")?;
+            fetcher.mutate_archive_account(password)?;
+            out.success(
+                JsonValue::new_object(),
+                "Your account has been archived. Logging in again will restore it.",
+            );
+        }
+        ("delete", Some(args)) => {
+            confirm(
+                args,
+                "Deleting your account cannot be undone. Are you sure?",
+            )?;
+            let password = read_secret(overrides, "Enter your password:")?;
+            fetcher.mutate_delete_account(password)?;
+            // the stored tokens belong to an account that no longer exists
+            let name = profile
+                .map(|profile| profile.name.clone())
+                .unwrap_or_default();
+            if config.profiles.remove(&name).is_some() {
+                if config.current.as_deref() == Some(&name[..]) {
+                    config.current = None;
+                }
+                config.save()?;
+            }
+            let mut document = JsonValue::new_object();
+            document["profile"] = name.into();
+            out.success(document, "Your account has been deleted.");
+        }
+        _ => panic!("Unknown argument"),
+    }
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use crate::api::error::BorzError;
+use crate::api::paginate::Paginator;
+use crate::commands::{fetcher_from_profile, resolve_thread};
+use crate::config::Profile;
+use crate::output::Output;
+use clap::ArgMatches;
+use json::JsonValue;
+
+fn message(
+    id: String,
+    author_id: String,
+    username: String,
+    created: String,
+    content: String,
+) -> JsonValue {
+    let mut node = JsonValue::new_object();
+    node["id"] = id.into();
+    node["author"]["id"] = author_id.into();
+    node["author"]["username"] = username.into();
+    node["created"] = created.into();
+    node["content"] = content.into();
+    node
+}
+
+// prints the opener and every reply of a thread, given either its id or its path
+// with --after, only the replies past that cursor are printed, so a thread can be followed
+pub fn cat(profile: Option<&Profile>, args: &ArgMatches, out: Output) -> Result<(), BorzError> {
+    let target = args.value_of("THREAD").unwrap();
+    let after = args.value_of("after").map(String::from);
+    let mut fetcher = fetcher_from_profile(profile)?;
+    let thread_id = if target.starts_with('/') {
+        resolve_thread(fetcher.clone(), target)?
+    } else {
+        String::from(target)
+    };
+    let thread = fetcher
+        .query_thread_content(thread_id.clone())?
+        .thread
+        .ok_or_else(|| BorzError::NotFound(format!("thread {}", thread_id)))?;
+    let mut replies_paginator = Paginator::starting_at(after.clone());
+    let replies =
+        replies_paginator.collect(|after| fetcher.query_replies(thread_id.clone(), after))?;
+    let mut lines = Vec::new();
+    if after.is_none() {
+        lines.push(thread.title.clone());
+        lines.push(String::new());
+        lines.push(format!("{} at {}", thread.author.username, thread.created));
+        lines.push(thread.content.clone());
+    }
+    let mut document = JsonValue::new_object();
+    document["title"] = thread.title.into();
+    document["thread"] = message(
+        thread.id,
+        thread.author.id,
+        thread.author.username,
+        thread.created,
+        thread.content,
+    );
+    document["replies"] = JsonValue::new_array();
+    for reply in replies {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(format!("{} at {}", reply.author.username, reply.created));
+        lines.push(reply.content.clone());
+        let _ = document["replies"].push(message(
+            reply.id,
+            reply.author.id,
+            reply.author.username,
+            reply.created,
+            reply.content,
+        ));
+    }
+    document["end_cursor"] = replies_paginator.end_cursor().into();
+    out.success(document, &lines.join("\n"));
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use crate::api::error::BorzError;
+use crate::commands::{fetcher_from_profile, given_or_prompt, read_secret};
+use crate::config::{Overrides, Profile};
+use crate::output::Output;
+use clap::ArgMatches;
+use json::JsonValue;
+
+// manages the secondary email, which can be swapped with the primary one once verified
+pub fn email(
+    profile: Option<&Profile>,
+    args: &ArgMatches,
+    overrides: &Overrides,
+    out: Output,
+) -> Result<(), BorzError> {
+    let fetcher = fetcher_from_profile(profile)?;
+    match args.subcommand() {
+        ("show", _) => {
+            let me = fetcher.query_me()?;
+            let secondary = me.secondary_email.filter(|email| !email.is_empty());
+            let mut document = JsonValue::new_object();
+            document["email"] = me.email.clone().into();
+            document["secondary_email"] = secondary.clone().into();
+            let text = format!(
+                "primary\t{}\nsecondary\t{}",
+                me.email,
+                secondary.unwrap_or_else(|| String::from("(none)"))
+            );
+            out.success(document, &text);
+        }
+        ("add", Some(args)) => {
+            let email = String::from(args.value_of("EMAIL").unwrap());
+            let password = read_secret(overrides, "Enter your password:")?;
+            fetcher.mutate_send_secondary_email_activation(email.clone(), password)?;
+            let mut document = JsonValue::new_object();
+            document["email"] = email.into();
+            out.success(
+                document,
+                "Please check the new address for a token, then run borz email verify",
+            );
+        }
+        ("verify", Some(args)) => {
+            let token = given_or_prompt(args.value_of("TOKEN"), "Enter the token from your email:");
+            fetcher.mutate_verify_secondary_email(token)?;
+            out.success(
+                JsonValue::new_object(),
+                "Your secondary email has been verified.",
+            );
+        }
+        ("swap", _) => {
+            let password = read_secret(overrides, "Enter your password:")?;
+            fetcher.mutate_swap_emails(password)?;
+            out.success(
+                JsonValue::new_object(),
+                "Your primary and secondary emails have been swapped.",
+            );
+        }
+        ("remove", _) => {
+            let password = read_secret(overrides, "Enter your password:")?;
+            fetcher.mutate_remove_secondary_email(password)?;
+            out.success(
+                JsonValue::new_object(),
+                "Your secondary email has been removed.",
+            );
+        }
+        _ => panic!("Unknown argument"),
+    }
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use crate::api::error::BorzError;
+use crate::api::fetch::slugify;
+use crate::commands::{fetcher_from_profile, normalize_path, read_description, resolve_group};
+use crate::config::Profile;
+use crate::output::Output;
+use clap::ArgMatches;
+use json::JsonValue;
+
+// manages the subgroup at a path such as /Universe/Science
+pub fn group(profile: Option<&Profile>, args: &ArgMatches, out: Output) -> Result<(), BorzError> {
+    let fetcher = fetcher_from_profile(profile)?;
+    match args.subcommand() {
+        ("join", Some(args)) => {
+            let path = normalize_path(args.value_of("PATH").unwrap());
+            let group = resolve_group(fetcher, &path)?;
+            group.mutate_join_subgroup(String::from(group.node_id()))?;
+            let mut document = JsonValue::new_object();
+            document["path"] = path.clone().into();
+            document["id"] = group.node_id().into();
+            out.success(document, &format!("You have joined {}.", path));
+        }
+        ("leave", Some(args)) => {
+            let path = normalize_path(args.value_of("PATH").unwrap());
+            let group = resolve_group(fetcher, &path)?;
+            group.mutate_leave_subgroup(String::from(group.node_id()))?;
+            let mut document = JsonValue::new_object();
+            document["path"] = path.clone().into();
+            document["id"] = group.node_id().into();
+            out.success(document, &format!("You have left {}.", path));
+        }
+        ("create", Some(args)) => {
+            let path = normalize_path(args.value_of("PATH").unwrap());
+            let (parent_path, name) = match path.rfind('/') {
+                Some(pos) if pos > 0 => (&path[..pos], &path[pos + 1..]),
+                _ => {
+                    return Err(BorzError::InvalidInput(String::from(
+                        "Please give the path of the new subgroup, such as /Universe/Science",
+                    )))
+                }
+            };
+            let slug = args
+                .value_of("slug")
+                .map(String::from)
+                .unwrap_or_else(|| slugify(name));
+            if slug.is_empty() {
+                return Err(BorzError::InvalidInput(String::from(
+                    "Please give a slug with --slug, as none could be made from the name",
+                )));
+            }
+            let description = String::from(args.value_of("description").unwrap_or(""));
+            let parent = resolve_group(fetcher, parent_path)?;
+            let id =
+                parent.mutate_create_subgroup(String::from(name), slug.clone(), description)?;
+            let mut document = JsonValue::new_object();
+            document["path"] = path.clone().into();
+            document["id"] = id.clone().into();
+            document["slug"] = slug.into();
+            out.success(document, &id);
+        }
+        ("describe", Some(args)) => {
+            let path = normalize_path(args.value_of("PATH").unwrap());
+            let group = resolve_group(fetcher, &path)?;
+            let mut document = JsonValue::new_object();
+            document["path"] = path.into();
+            document["id"] = group.node_id().into();
+            if args.is_present("set") {
+                let description = read_description(args)?;
+                group.mutate_update_subgroup(description.clone())?;
+                document["description"] = description.into();
+                out.success(document, "The description has been updated.");
+            } else {
+                let subgroup = group.query_subgroup()?;
+                document["name"] = subgroup.name.into();
+                document["slug"] = subgroup.slug.into();
+                document["description"] = subgroup.description.clone().into();
+                out.success(document, &subgroup.description);
+            }
+        }
+        _ => panic!("Unknown argument"),
+    }
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use crate::api::error::BorzError;
+use crate::api::paginate::Paginator;
+use crate::commands::{fetcher_from_profile, normalize_path, resolve_group};
+use crate::config::Profile;
+use crate::output::Output;
+use clap::ArgMatches;
+use json::JsonValue;
+
+// prints one tab separated line per child subgroup and thread of the group
+pub fn ls(profile: Option<&Profile>, args: &ArgMatches, out: Output) -> Result<(), BorzError> {
+    let path = normalize_path(args.value_of("PATH").unwrap_or("/Universe"));
+    let mut fetcher = resolve_group(fetcher_from_profile(profile)?, &path)?;
+    let subgroups = Paginator::new().collect(|after| fetcher.query_subgroups(after))?;
+    let threads = Paginator::new().collect(|after| fetcher.query_threads(after))?;
+    let mut document = JsonValue::new_object();
+    document["path"] = path.clone().into();
+    document["id"] = fetcher.node_id().into();
+    document["subgroups"] = JsonValue::new_array();
+    document["threads"] = JsonValue::new_array();
+    let mut lines = Vec::new();
+    for subgroup in subgroups {
+        let subgroup_path = format!("{}/{}", path, subgroup.name);
+        lines.push(format!("group\t{}\t{}", subgroup_path, subgroup.id));
+        let mut node = JsonValue::new_object();
+        node["id"] = subgroup.id.into();
+        node["name"] = subgroup.name.into();
+        node["slug"] = subgroup.slug.into();
+        node["description"] = subgroup.description.into();
+        node["path"] = subgroup_path.into();
+        let _ = document["subgroups"].push(node);
+    }
+    for thread in threads {
+        lines.push(format!(
+            "thread\t{}\t{}\t{}",
+            thread.title, thread.author.username, thread.id
+        ));
+        let mut node = JsonValue::new_object();
+        node["id"] = thread.id.into();
+        node["title"] = thread.title.into();
+        node["author"]["id"] = thread.author.id.into();
+        node["author"]["username"] = thread.author.username.into();
+        let _ = document["threads"].push(node);
+    }
+    out.success(document, &lines.join("\n"));
+    Ok(())
+}
@@ -0,0 +1,223 @@
+use crate::api::error::BorzError;
+use crate::api::fetch::APIFetcher;
+use crate::api::paginate::Paginator;
+use crate::api::token::TokenManager;
+use crate::config::{Overrides, Profile};
+use crate::TOP_LEVEL_ID;
+use clap::ArgMatches;
+use rpassword::read_password;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, IsTerminal, Read};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::PathBuf;
+use std::process::{self, Command};
+use std::time::{SystemTime, UNIX_EPOCH};
+use url::Url;
+
+pub mod account;
+pub mod cat;
+pub mod email;
+pub mod group;
+pub mod ls;
+pub mod password;
+pub mod post;
+pub mod profile;
+pub mod reply;
+pub mod whoami;
+
+pub fn fetcher_from_profile(profile: Option<&Profile>) -> Result<APIFetcher, BorzError> {
+    let profile = profile.ok_or(BorzError::NotLoggedIn)?;
+    Ok(APIFetcher::authenticated(
+        Url::parse(&profile.server)?,
+        TOP_LEVEL_ID.clone(),
+        TokenManager::new(
+            profile.name.clone(),
+            profile.token.clone(),
+            profile.refresh_token.clone(),
+        ),
+    ))
+}
+
+// walks a path like /Universe/Science/Physics down from the top level group
+pub fn resolve_group(mut fetcher: APIFetcher, path: &str) -> Result<APIFetcher, BorzError> {
+    let mut names = path.split('/').filter(|name| !name.is_empty());
+    if names.next() != Some("Universe") {
+        return Err(BorzError::InvalidInput(format!(
+            "Group paths must start with /Universe, got {}",
+            path
+        )));
+    }
+    for name in names {
+        fetcher = fetcher.child_by_name(name)?;
+    }
+    Ok(fetcher)
+}
+
+// finds the id of the thread at a path like /Universe/Science/Thread Title
+pub fn resolve_thread(fetcher: APIFetcher, path: &str) -> Result<String, BorzError> {
+    let path = normalize_path(path);
+    let (group_path, title) = match path.rfind('/') {
+        Some(pos) if pos > 0 => (&path[..pos], &path[pos + 1..]),
+        _ => {
+            return Err(BorzError::InvalidInput(format!(
+                "{} is not the path of a thread",
+                path
+            )))
+        }
+    };
+    let mut fetcher = resolve_group(fetcher, group_path)?;
+    let threads = Paginator::new().collect(|after| fetcher.query_threads(after))?;
+    threads
+        .into_iter()
+        .find(|thread| thread.title == title)
+        .map(|thread| thread.id)
+        .ok_or_else(|| BorzError::NotFound(format!("thread {}", path)))
+}
+
+// joins the components of a path back together without any stray slashes
+pub fn normalize_path(path: &str) -> String {
+    let names: Vec<&str> = path.split('/').filter(|name| !name.is_empty()).collect();
+    format!("/{}", names.join("/"))
+}
+
+// takes the body of a message from --file, from piped stdin, or from $EDITOR on a temporary file
+pub fn read_content(args: &ArgMatches) -> Result<String, BorzError> {
+    let content = read_text(args)?;
+    if content.is_empty() {
+        return Err(BorzError::InvalidInput(String::from(
+            "The message cannot be empty",
+        )));
+    }
+    Ok(content)
+}
+
+// takes a group description from the same places as a message, where an empty one clears it
+pub fn read_description(args: &ArgMatches) -> Result<String, BorzError> {
+    read_text(args)
+}
+
+fn read_text(args: &ArgMatches) -> Result<String, BorzError> {
+    let content = if let Some(file) = args.value_of("file") {
+        fs::read_to_string(file)?
+    } else if !io::stdin().is_terminal() {
+        let mut content = String::new();
+        io::stdin().read_to_string(&mut content)?;
+        content
+    } else {
+        edit_in_editor()?
+    };
+    Ok(String::from(content.trim()))
+}
+
+// opens $VISUAL or $EDITOR on an empty private file and returns what was saved in it
+fn edit_in_editor() -> Result<String, BorzError> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| String::from("vi"));
+    let mut words = editor.split_whitespace();
+    let program = words
+        .next()
+        .ok_or_else(|| BorzError::InvalidInput(String::from("$EDITOR is empty")))?;
+    let path = create_temp_file()?;
+    let status = Command::new(program).args(words).arg(&path).status();
+    let content = fs::read_to_string(&path);
+    // the file is removed whether or not the editor could be run
+    let removed = fs::remove_file(&path);
+    if !status?.success() {
+        return Err(BorzError::InvalidInput(format!(
+            "{} exited unsuccessfully",
+            editor
+        )));
+    }
+    removed?;
+    Ok(content?)
+}
+
+// the temporary directory is shared, so the file gets an unpredictable name, is never opened
+// through an existing file or symlink and is only readable by the user
+fn create_temp_file() -> Result<PathBuf, BorzError> {
+    let mut attempts = 0;
+    loop {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.subsec_nanos())
+            .unwrap_or(0);
+        let path =
+            env::temp_dir().join(format!("borz-{}-{}-{}.txt", process::id(), nanos, attempts));
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        match options.open(&path) {
+            Ok(_) => return Ok(path),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists && attempts < 10 => attempts += 1,
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+// prompts go to stderr so stdout only carries the result
+pub fn prompt(message: &str) {
+    eprintln!("{}", message);
+}
+
+// uses the value given on the command line, or asks for it
+pub fn given_or_prompt(given: Option<&str>, message: &str) -> String {
+    match given {
+        Some(value) => String::from(value),
+        None => {
+            prompt(message);
+            read_line()
+        }
+    }
+}
+
+// reads a password from the terminal without echoing it, or a line of stdin with --password-stdin
+pub fn read_secret(overrides: &Overrides, message: &str) -> Result<String, BorzError> {
+    if overrides.password_stdin {
+        let mut line = String::new();
+        io::stdin().read_line(&mut line)?;
+        return Ok(String::from(line.trim_end_matches(&['\r', '\n'][..])));
+    }
+    prompt(message);
+    Ok(read_password()?)
+}
+
+pub fn read_line() -> String {
+    let mut str: String = String::new();
+    io::stdin().read_line(&mut str).unwrap();
+    String::from(str.trim())
+}
+
+pub fn server_or_prompt(given: Option<&str>) -> Result<Url, BorzError> {
+    Ok(Url::parse(
+        &given_or_prompt(given, "Enter the address of the server:")[..],
+    )?)
+}
+
+// asks for a new password twice, unless it is piped in
+pub fn read_new_password(overrides: &Overrides) -> Result<String, BorzError> {
+    let password = read_secret(overrides, "Enter a new password:")?;
+    if !overrides.password_stdin
+        && password != read_secret(overrides, "Re-enter the new password:")?
+    {
+        return Err(BorzError::InvalidInput(String::from(
+            "Entered passwords must match!",
+        )));
+    }
+    Ok(password)
+}
+
+// asks a yes or no question, which --yes answers ahead of time
+pub fn confirm(args: &ArgMatches, message: &str) -> Result<(), BorzError> {
+    if args.is_present("yes") {
+        return Ok(());
+    }
+    prompt(&format!("{} [y/N]", message));
+    match &read_line().to_lowercase()[..] {
+        "y" | "yes" => Ok(()),
+        _ => Err(BorzError::InvalidInput(String::from("Cancelled"))),
+    }
+}
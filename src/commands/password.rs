@@ -0,0 +1,66 @@
+use crate::api::error::BorzError;
+use crate::api::fetch::APIFetcher;
+use crate::commands::{
+    fetcher_from_profile, given_or_prompt, read_new_password, read_secret, server_or_prompt,
+};
+use crate::config::{Overrides, Profile};
+use crate::output::Output;
+use crate::TOP_LEVEL_ID;
+use clap::ArgMatches;
+use json::JsonValue;
+
+// the reset flows work while logged out, so the server falls back to asking for it
+fn anonymous_fetcher(
+    profile: Option<&Profile>,
+    overrides: &Overrides,
+) -> Result<APIFetcher, BorzError> {
+    let server = overrides
+        .server
+        .as_deref()
+        .or_else(|| profile.map(|profile| &profile.server[..]));
+    Ok(APIFetcher::new(
+        server_or_prompt(server)?,
+        TOP_LEVEL_ID.clone(),
+    ))
+}
+
+// sends a reset email, resets a password with the token from it, or changes the password of the profile
+pub fn password(
+    profile: Option<&Profile>,
+    args: &ArgMatches,
+    overrides: &Overrides,
+    out: Output,
+) -> Result<(), BorzError> {
+    match args.subcommand() {
+        ("forgot", Some(args)) => {
+            let fetcher = anonymous_fetcher(profile, overrides)?;
+            let email = given_or_prompt(args.value_of("EMAIL"), "Enter your email:");
+            fetcher.mutate_send_password_reset_email(email.clone())?;
+            let mut document = JsonValue::new_object();
+            document["email"] = email.into();
+            out.success(
+                document,
+                "Please check your email for a token, then run borz password reset",
+            );
+        }
+        ("reset", Some(args)) => {
+            let fetcher = anonymous_fetcher(profile, overrides)?;
+            let token = given_or_prompt(args.value_of("TOKEN"), "Enter the token from your email:");
+            let password = read_new_password(overrides)?;
+            fetcher.mutate_password_reset(token, password)?;
+            out.success(
+                JsonValue::new_object(),
+                "Your password has been reset. You may now log in by running borz login",
+            );
+        }
+        ("change", _) => {
+            let fetcher = fetcher_from_profile(profile)?;
+            let old_password = read_secret(overrides, "Enter your current password:")?;
+            let password = read_new_password(overrides)?;
+            fetcher.mutate_password_change(old_password, password)?;
+            out.success(JsonValue::new_object(), "Your password has been changed.");
+        }
+        _ => panic!("Unknown argument"),
+    }
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use crate::api::error::BorzError;
+use crate::commands::{fetcher_from_profile, normalize_path, read_content, resolve_group};
+use crate::config::Profile;
+use crate::output::Output;
+use clap::ArgMatches;
+use json::JsonValue;
+
+// creates a thread in a group and prints the id of the new thread
+pub fn post(profile: Option<&Profile>, args: &ArgMatches, out: Output) -> Result<(), BorzError> {
+    let path = normalize_path(args.value_of("GROUP").unwrap());
+    let title = String::from(args.value_of("title").unwrap().trim());
+    if title.is_empty() {
+        return Err(BorzError::InvalidInput(String::from(
+            "The thread needs a title",
+        )));
+    }
+    let mut fetcher = resolve_group(fetcher_from_profile(profile)?, &path)?;
+    let content = read_content(args)?;
+    let thread = fetcher
+        .mutate_create_thread(title, content)?
+        .create_thread
+        .and_then(|payload| payload.thread)
+        .ok_or(BorzError::MissingData("thread"))?;
+    let mut document = JsonValue::new_object();
+    document["id"] = thread.id.clone().into();
+    document["title"] = thread.title.into();
+    document["group"] = path.into();
+    out.success(document, &thread.id);
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use crate::api::error::BorzError;
+use crate::config::Config;
+use crate::output::Output;
+use clap::ArgMatches;
+use json::JsonValue;
+
+fn require(config: &Config, name: &str) -> Result<(), BorzError> {
+    if config.profiles.contains_key(name) {
+        Ok(())
+    } else {
+        Err(BorzError::NotFound(format!("profile {}", name)))
+    }
+}
+
+// lists, switches between and forgets the profiles stored in the config
+pub fn profile(config: &mut Config, args: &ArgMatches, out: Output) -> Result<(), BorzError> {
+    let current = config.selected(None);
+    match args.subcommand() {
+        ("list", _) => {
+            let mut document = JsonValue::new_object();
+            document["current"] = current.clone().into();
+            document["profiles"] = JsonValue::new_array();
+            let mut lines = Vec::new();
+            for (name, profile) in config.profiles.iter() {
+                let marker = if *name == current { "*" } else { " " };
+                lines.push(format!(
+                    "{} {}\t{}\t{}",
+                    marker, name, profile.username, profile.server
+                ));
+                let mut node = JsonValue::new_object();
+                node["name"] = name.clone().into();
+                node["username"] = profile.username.clone().into();
+                node["server"] = profile.server.clone().into();
+                node["current"] = (*name == current).into();
+                let _ = document["profiles"].push(node);
+            }
+            out.success(document, &lines.join("\n"));
+        }
+        ("use", Some(args)) => {
+            let name = args.value_of("NAME").unwrap();
+            require(config, name)?;
+            config.current = Some(String::from(name));
+            config.save()?;
+            let mut document = JsonValue::new_object();
+            document["current"] = name.into();
+            out.success(document, &format!("Now using the {} profile.", name));
+        }
+        ("remove", Some(args)) => {
+            let name = args.value_of("NAME").unwrap();
+            require(config, name)?;
+            config.profiles.remove(name);
+            if name == current {
+                config.current = None;
+            }
+            config.save()?;
+            let mut document = JsonValue::new_object();
+            document["removed"] = name.into();
+            out.success(document, &format!("Removed the {} profile.", name));
+        }
+        _ => panic!("Unknown argument"),
+    }
+    Ok(())
+}
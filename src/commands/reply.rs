@@ -0,0 +1,28 @@
+use crate::api::error::BorzError;
+use crate::commands::{fetcher_from_profile, read_content, resolve_thread};
+use crate::config::Profile;
+use crate::output::Output;
+use clap::ArgMatches;
+use json::JsonValue;
+
+// replies to a thread, given either its id or its path, and prints the id of the new reply
+pub fn reply(profile: Option<&Profile>, args: &ArgMatches, out: Output) -> Result<(), BorzError> {
+    let target = args.value_of("THREAD").unwrap();
+    let mut fetcher = fetcher_from_profile(profile)?;
+    let thread_id = if target.starts_with('/') {
+        resolve_thread(fetcher.clone(), target)?
+    } else {
+        String::from(target)
+    };
+    let content = read_content(args)?;
+    let reply = fetcher
+        .mutate_thread_reply(thread_id.clone(), content)?
+        .create_reply
+        .and_then(|payload| payload.reply)
+        .ok_or(BorzError::MissingData("reply"))?;
+    let mut document = JsonValue::new_object();
+    document["id"] = reply.id.clone().into();
+    document["thread"] = thread_id.into();
+    out.success(document, &reply.id);
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use crate::api::error::BorzError;
+use crate::api::paginate::Paginator;
+use crate::commands::fetcher_from_profile;
+use crate::config::Profile;
+use crate::output::Output;
+use json::JsonValue;
+
+// checks the stored session and prints who it belongs to
+// exits with 0 when it works, 3 when logged out and 4 when it has expired
+pub fn whoami(profile: Option<&Profile>, out: Output) -> Result<(), BorzError> {
+    let fetcher = fetcher_from_profile(profile)?;
+    // an expired access token is fine as long as the refresh token still works
+    let token_valid = fetcher.mutate_verify_token()?;
+    let me = fetcher.query_me()?;
+    let subgroups = Paginator::new().collect(|after| fetcher.query_memberships(after))?;
+    let verified = me.verified.unwrap_or(false);
+    let archived = me.archived.unwrap_or(false);
+    let mut lines = vec![
+        format!("username\t{}", me.username),
+        format!("email\t{}", me.email),
+        format!("joined\t{}", me.date_joined),
+        format!("verified\t{}", verified),
+        format!("archived\t{}", archived),
+        format!("token\t{}", if token_valid { "valid" } else { "refreshed" }),
+    ];
+    let mut document = JsonValue::new_object();
+    document["username"] = me.username.into();
+    document["email"] = me.email.into();
+    document["date_joined"] = me.date_joined.into();
+    document["verified"] = verified.into();
+    document["archived"] = archived.into();
+    document["token_valid"] = token_valid.into();
+    document["subgroups"] = JsonValue::new_array();
+    for subgroup in subgroups {
+        lines.push(format!("member\t{}\t{}", subgroup.name, subgroup.id));
+        let mut node = JsonValue::new_object();
+        node["id"] = subgroup.id.into();
+        node["name"] = subgroup.name.into();
+        let _ = document["subgroups"].push(node);
+    }
+    out.success(document, &lines.join("\n"));
+    Ok(())
+}
@@ -0,0 +1,380 @@
+use crate::api::error::BorzError;
+use crate::{CONFIG_FILE_PATH, CONFIG_FOLDER_PATH};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::thread;
+use std::time::Duration;
+
+// bumped whenever the layout of the file changes, see Config::migrate
+pub const CONFIG_VERSION: u64 = 1;
+pub const DEFAULT_PROFILE: &str = "default";
+// how long a client waits for another one to finish writing the file
+const LOCK_ATTEMPTS: u32 = 50;
+const LOCK_WAIT: Duration = Duration::from_millis(20);
+// a lock older than this was left behind by a client that stopped while holding it
+const LOCK_STALE: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Profile {
+    // filled in from the key the profile is stored under
+    #[serde(skip)]
+    pub name: String,
+    pub token: String,
+    pub refresh_token: String,
+    pub username: String,
+    pub server: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    pub version: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            version: CONFIG_VERSION,
+            current: None,
+            profiles: BTreeMap::new(),
+        }
+    }
+}
+
+// values given with flags or environment variables, which take precedence over the stored profile
+#[derive(Debug, Clone, Default)]
+pub struct Overrides {
+    pub server: Option<String>,
+    pub username: Option<String>,
+    pub password_stdin: bool,
+    pub token: Option<String>,
+    pub refresh_token: Option<String>,
+}
+
+impl Overrides {
+    // tokens are only read from the environment so they never show up in the process list
+    pub fn new(server: Option<&str>, username: Option<&str>, password_stdin: bool) -> Overrides {
+        let var = |name: &str| env::var(name).ok().filter(|value| !value.is_empty());
+        Overrides {
+            server: server.map(String::from),
+            username: username.map(String::from),
+            password_stdin,
+            token: var("BORZ_TOKEN"),
+            refresh_token: var("BORZ_REFRESH_TOKEN"),
+        }
+    }
+}
+
+impl Config {
+    // a missing file is an empty config, and a corrupt one is set aside so the user can log in again
+    // while one written by a newer client is an error
+    pub fn load() -> Result<Config, BorzError> {
+        fs::create_dir_all(CONFIG_FOLDER_PATH.clone())?;
+        Config::load_from(&CONFIG_FILE_PATH)
+    }
+
+    fn load_from(path: &str) -> Result<Config, BorzError> {
+        let content = match fs::read_to_string(path) {
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Config::default()),
+            res => res?,
+        };
+        let value: Value = match serde_json::from_str(&content) {
+            Ok(value) => value,
+            Err(e) => return Config::set_aside(path, e.to_string()),
+        };
+        // a file from a newer client is left alone so going back to it keeps every profile
+        Config::check_version(&value)?;
+        match Config::migrate(value) {
+            Ok(config) => Ok(config),
+            Err(reason) => Config::set_aside(path, reason),
+        }
+    }
+
+    fn set_aside(path: &str, reason: String) -> Result<Config, BorzError> {
+        let backup = format!("{}.bak", path);
+        fs::rename(path, &backup)?;
+        eprintln!(
+            "The configuration was unreadable ({}) and has been moved to {}. Please log in again.",
+            reason, backup
+        );
+        Ok(Config::default())
+    }
+
+    fn check_version(value: &Value) -> Result<(), BorzError> {
+        let version = value.get("version").and_then(Value::as_u64).unwrap_or(0);
+        if version > CONFIG_VERSION {
+            return Err(BorzError::Config(format!(
+                "version {} is newer than this client",
+                version
+            )));
+        }
+        Ok(())
+    }
+
+    // files written before profiles existed have no version and keep a single account at the top level
+    fn migrate(mut value: Value) -> Result<Config, String> {
+        let version = value.get("version").and_then(Value::as_u64).unwrap_or(0);
+        let object = value
+            .as_object_mut()
+            .ok_or_else(|| String::from("not an object"))?;
+        if version == 0 && object.contains_key("token") {
+            let mut profile = serde_json::Map::new();
+            for key in ["token", "refresh_token", "username", "server"].iter() {
+                if let Some(field) = object.remove(*key) {
+                    profile.insert(String::from(*key), field);
+                }
+            }
+            let mut profiles = serde_json::Map::new();
+            profiles.insert(String::from(DEFAULT_PROFILE), Value::Object(profile));
+            object.insert(String::from("profiles"), Value::Object(profiles));
+            object.insert(String::from("current"), Value::from(DEFAULT_PROFILE));
+        }
+        object.insert(String::from("version"), Value::from(CONFIG_VERSION));
+        serde_json::from_value(value).map_err(|e| e.to_string())
+    }
+
+    // rereads the file and writes it back if the change asks to, holding a lock meanwhile
+    // so clients doing the same at once do not undo each other's changes
+    pub fn update<F>(change: F) -> Result<(), BorzError>
+    where
+        F: FnOnce(&mut Config) -> bool,
+    {
+        Config::update_at(&CONFIG_FILE_PATH, change)
+    }
+
+    fn update_at<F>(path: &str, change: F) -> Result<(), BorzError>
+    where
+        F: FnOnce(&mut Config) -> bool,
+    {
+        let _lock = ConfigLock::acquire(format!("{}.lock", path))?;
+        // unlike load, an unreadable file is left for the next interactive run to deal with
+        let mut config = match fs::read_to_string(path) {
+            Err(e) if e.kind() == ErrorKind::NotFound => Config::default(),
+            res => {
+                let value: Value =
+                    serde_json::from_str(&res?).map_err(|e| BorzError::Config(e.to_string()))?;
+                Config::check_version(&value)?;
+                Config::migrate(value).map_err(BorzError::Config)?
+            }
+        };
+        if change(&mut config) {
+            config.save_to(path)?;
+        }
+        Ok(())
+    }
+
+    // the tokens are written to a private temporary file which is renamed over the config
+    // so the file is never half written or readable by other users
+    pub fn save(&self) -> Result<(), BorzError> {
+        fs::create_dir_all(CONFIG_FOLDER_PATH.clone())?;
+        self.save_to(&CONFIG_FILE_PATH)
+    }
+
+    fn save_to(&self, path: &str) -> Result<(), BorzError> {
+        let content =
+            serde_json::to_string_pretty(self).map_err(|e| BorzError::Config(e.to_string()))?;
+        let tmp_path = format!("{}.tmp", path);
+        // the mode only applies to new files, so never reuse a leftover one
+        let _ = fs::remove_file(&tmp_path);
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        let mut file = options.open(&tmp_path)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    // the profile named with --profile, otherwise the one chosen with borz profile use
+    pub fn selected(&self, name: Option<&str>) -> String {
+        match (name, &self.current) {
+            (Some(name), _) => String::from(name),
+            (None, Some(current)) => current.clone(),
+            (None, None) => String::from(DEFAULT_PROFILE),
+        }
+    }
+
+    pub fn profile(&self, name: &str) -> Option<Profile> {
+        self.profiles.get(name).map(|profile| Profile {
+            name: String::from(name),
+            ..profile.clone()
+        })
+    }
+
+    // the stored profile with the overrides applied, which is enough to log in with on its own
+    // when BORZ_TOKEN and a server are given
+    pub fn session(&self, name: &str, overrides: &Overrides) -> Option<Profile> {
+        let mut profile = match (self.profile(name), &overrides.token) {
+            (Some(profile), _) => profile,
+            (None, Some(_)) => Profile::default(),
+            (None, None) => return None,
+        };
+        if let Some(token) = &overrides.token {
+            profile.token = token.clone();
+            profile.refresh_token = overrides.refresh_token.clone().unwrap_or_default();
+            // rotated tokens are not saved over a stored profile they did not come from
+            profile.name = String::new();
+        }
+        if let Some(server) = &overrides.server {
+            profile.server = server.clone();
+        }
+        if let Some(username) = &overrides.username {
+            profile.username = username.clone();
+        }
+        if profile.server.is_empty() {
+            return None;
+        }
+        Some(profile)
+    }
+}
+
+// held while a client rewrites the file, and removed when dropped
+struct ConfigLock {
+    path: String,
+}
+
+impl ConfigLock {
+    fn acquire(path: String) -> Result<ConfigLock, BorzError> {
+        for _ in 0..LOCK_ATTEMPTS {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(ConfigLock { path }),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    let stale = fs::metadata(&path)
+                        .and_then(|metadata| metadata.modified())
+                        .ok()
+                        .and_then(|modified| modified.elapsed().ok())
+                        .is_some_and(|age| age > LOCK_STALE);
+                    if stale {
+                        let _ = fs::remove_file(&path);
+                    } else {
+                        thread::sleep(LOCK_WAIT);
+                    }
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Err(BorzError::Config(String::from(
+            "it is being written by another borz process",
+        )))
+    }
+}
+
+impl Drop for ConfigLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn migrates_the_single_account_file_to_the_default_profile() {
+        let config = Config::migrate(json!({
+            "token": "token",
+            "refresh_token": "refresh",
+            "username": "alice",
+            "server": "https://borz.example/graphql"
+        }))
+        .unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.current.as_deref(), Some(DEFAULT_PROFILE));
+        assert_eq!(config.profiles.len(), 1);
+        let profile = &config.profiles[DEFAULT_PROFILE];
+        assert_eq!(profile.token, "token");
+        assert_eq!(profile.refresh_token, "refresh");
+        assert_eq!(profile.username, "alice");
+        assert_eq!(profile.server, "https://borz.example/graphql");
+    }
+
+    #[test]
+    fn reads_an_empty_object_as_an_empty_config() {
+        let config = Config::migrate(json!({})).unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.current, None);
+        assert!(config.profiles.is_empty());
+    }
+
+    #[test]
+    fn keeps_a_current_config_as_it_is() {
+        let config = Config::migrate(json!({
+            "version": CONFIG_VERSION,
+            "current": "work",
+            "profiles": {
+                "work": {
+                    "token": "token",
+                    "refresh_token": "refresh",
+                    "username": "bob",
+                    "server": "https://work.example/graphql"
+                }
+            }
+        }))
+        .unwrap();
+        assert_eq!(config.current.as_deref(), Some("work"));
+        assert_eq!(config.profiles["work"].username, "bob");
+    }
+
+    #[test]
+    fn rejects_a_newer_version() {
+        match Config::check_version(&json!({ "version": CONFIG_VERSION + 1 })) {
+            Err(BorzError::Config(reason)) => assert!(reason.contains("newer than this client")),
+            other => panic!("expected a config error, got {:?}", other),
+        }
+        assert!(Config::check_version(&json!({ "version": CONFIG_VERSION })).is_ok());
+    }
+
+    #[test]
+    fn leaves_a_newer_file_in_place() {
+        let dir = env::temp_dir().join(format!("borz-config-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.json").to_str().unwrap().to_string();
+        let content = json!({ "version": CONFIG_VERSION + 1, "profiles": {} }).to_string();
+        fs::write(&path, &content).unwrap();
+        let res = Config::load_from(&path);
+        let backup_exists = fs::metadata(format!("{}.bak", path)).is_ok();
+        let kept = fs::read_to_string(&path).ok();
+        fs::remove_dir_all(&dir).unwrap();
+        match res {
+            Err(BorzError::Config(reason)) => assert!(reason.contains("newer than this client")),
+            other => panic!("expected a config error, got {:?}", other),
+        }
+        assert!(!backup_exists);
+        assert_eq!(kept, Some(content));
+    }
+
+    #[test]
+    fn updates_a_profile_and_releases_the_lock() {
+        let dir = env::temp_dir().join(format!("borz-config-update-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.json").to_str().unwrap().to_string();
+        let content = json!({
+            "version": CONFIG_VERSION,
+            "profiles": { "default": { "token": "old", "refresh_token": "old", "username": "alice", "server": "" } }
+        });
+        fs::write(&path, content.to_string()).unwrap();
+        let res = Config::update_at(&path, |config| {
+            config.profiles.get_mut(DEFAULT_PROFILE).unwrap().token = String::from("new");
+            true
+        });
+        let token =
+            Config::load_from(&path).map(|config| config.profiles[DEFAULT_PROFILE].token.clone());
+        let locked = fs::metadata(format!("{}.lock", path)).is_ok();
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(res.is_ok());
+        assert_eq!(token.ok().as_deref(), Some("new"));
+        assert!(!locked);
+    }
+}
@@ -1,16 +1,19 @@
+use api::error::BorzError;
 use clap::ArgMatches;
+use commands::{given_or_prompt, prompt, read_secret, server_or_prompt};
+use config::{Config, Overrides, Profile};
 use crossbeam_channel::{select, tick, unbounded, Receiver};
 use crossterm::cursor;
-use crossterm::event::{Event, KeyCode, KeyModifiers};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
 use crossterm::execute;
 use crossterm::terminal;
 use dirs::home_dir;
 use json::JsonValue;
 use lazy_static::lazy_static;
-use rpassword::read_password;
+use output::Output;
 use std::fs;
-use std::io;
-use std::path::Path;
+use std::io::{self, IsTerminal};
+use std::process;
 use std::thread;
 use std::time::Duration;
 use tui::backend::CrosstermBackend;
@@ -20,6 +23,9 @@ use url::Url;
 mod api;
 mod app;
 mod args;
+mod commands;
+mod config;
+mod output;
 mod ui;
 mod widgets;
 
@@ -30,27 +36,13 @@ lazy_static! {
     pub static ref TOP_LEVEL_ID: String = String::from("U3ViZ3JvdXBOb2RlOjE=");
 }
 
-fn load_config() -> JsonValue {
-    fs::create_dir_all(CONFIG_FOLDER_PATH.clone()).unwrap();
-    if !Path::new(&CONFIG_FILE_PATH.clone()).is_file() {
-        fs::write(CONFIG_FILE_PATH.clone(), "{}").unwrap();
-    }
-    let content = fs::read_to_string(CONFIG_FILE_PATH.clone()).unwrap();
-    match json::parse(&content[..]) {
-        Err(_) => {
-            panic!("Bad JSON")
-        }
-        Ok(obj) => {
-            return obj;
-        }
-    }
-}
-
 fn setup_terminal() {
     let mut stdout = io::stdout();
     execute!(stdout, terminal::EnterAlternateScreen).unwrap();
     execute!(stdout, cursor::Hide).unwrap();
     execute!(stdout, terminal::Clear(terminal::ClearType::All)).unwrap();
+    // pasted text arrives as one event instead of a key press for each character
+    execute!(stdout, event::EnableBracketedPaste).unwrap();
     terminal::enable_raw_mode().unwrap();
 }
 
@@ -58,165 +50,393 @@ fn cleanup_terminal() {
     let mut stdout = io::stdout();
     execute!(stdout, cursor::MoveTo(0, 0)).unwrap();
     execute!(stdout, terminal::Clear(terminal::ClearType::All)).unwrap();
+    execute!(stdout, event::DisableBracketedPaste).unwrap();
     execute!(stdout, terminal::LeaveAlternateScreen).unwrap();
     execute!(stdout, cursor::Show).unwrap();
     terminal::disable_raw_mode().unwrap();
 }
 
+#[allow(clippy::needless_return)]
 fn setup_ui_events() -> Receiver<Event> {
     let (tx, rx) = unbounded();
     thread::spawn(move || loop {
-        match tx.send(crossterm::event::read().unwrap()) {
-            Ok(_) => {}
-            Err(_) => {}
+        if let Ok(event) = event::read() {
+            let _ = tx.send(event);
         }
     });
     return rx;
 }
 
-fn launch_app(args: ArgMatches, config: JsonValue) {
-    if !config.has_key("token")
-        || !config.has_key("refresh_token")
-        || !config.has_key("username")
-        || !config.has_key("server")
-    {
-        println!("Please run Borz login or Borz signup to log in first");
-        return;
-    }
+fn launch_app(profile: Option<Profile>) -> Result<(), BorzError> {
+    let profile = profile.ok_or(BorzError::NotLoggedIn)?;
     let backend = CrosstermBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend).unwrap();
     setup_terminal();
     let ticker = tick(Duration::from_secs_f64(0.4));
     let ui_events_receiver = setup_ui_events();
-    let mut app_instance = app::App::new(args, config);
-    app_instance.start();
+    let mut app_instance = app::App::new(profile);
+    let updates_receiver = app_instance.updates();
+    if let Err(e) = app_instance.start() {
+        cleanup_terminal();
+        return Err(e);
+    }
     ui::draw(&mut terminal, &mut app_instance);
     loop {
         select! {
             recv(ticker) -> _ => {
                 app_instance.tick();
             }
+            recv(updates_receiver) -> update => {
+                if let Ok(update) = update {
+                    update(&mut app_instance);
+                }
+            }
             recv(ui_events_receiver) -> message => {
                 match message {
-                    Err(e) => {
-                        println!("{}", e);
-                    }
-                    Ok(_) => (),
-                }
-                match message.unwrap() {
-                    Event::Key(key_event) => {
-                        if key_event.modifiers == KeyModifiers::CONTROL {
-                            if let KeyCode::Char(c) = key_event.code {
-                                if c == 'c' {
-                                    break;
-                                }
-                            }
-                        } else {
-                            let cont = app_instance.update(key_event);
-                            if !cont {
-                                break;
-                            }
+                    // some terminals also report keys being released
+                    Ok(Event::Key(key_event)) if key_event.kind == KeyEventKind::Press => {
+                        if key_event.modifiers == KeyModifiers::CONTROL
+                            && key_event.code == KeyCode::Char('c')
+                        {
+                            break;
+                        }
+                        // other control keys reach the editor for undo and word movement
+                        let cont = app_instance.update(key_event);
+                        if !cont {
+                            break;
                         }
                     }
-                    _ => (),
+                    Ok(Event::Paste(text)) => app_instance.paste(text),
+                    _ => {}
                 }
             }
         };
+        // the first page failing to load leaves nothing to show
+        if !app_instance.is_running() {
+            break;
+        }
         ui::draw(&mut terminal, &mut app_instance);
     }
     cleanup_terminal();
+    if !app_instance.is_running() {
+        if let Some(e) = app_instance.take_error() {
+            return Err(e);
+        }
+    }
+    Ok(())
 }
 
-fn read_line() -> String {
-    let mut str: String = String::new();
-    io::stdin().read_line(&mut str).unwrap();
-    return String::from(str.trim());
+fn login(
+    out: Output,
+    config: &mut Config,
+    profile: &str,
+    overrides: &Overrides,
+) -> Result<(), BorzError> {
+    let server = server_or_prompt(overrides.server.as_deref())?;
+    let username = given_or_prompt(overrides.username.as_deref(), "Enter your username:");
+    let password = read_secret(overrides, "Enter your password:")?;
+    if username.is_empty() || password.is_empty() {
+        return Err(BorzError::InvalidInput(String::from(
+            "Username and password cannot be empty!",
+        )));
+    }
+    let mut fetcher = api::fetch::APIFetcher::new(server.clone(), TOP_LEVEL_ID.clone());
+    let token_auth = fetcher
+        .mutate_auth(username, password)?
+        .token_auth
+        .ok_or(BorzError::MissingData("tokenAuth"))?;
+    if !token_auth.success.unwrap_or(false) {
+        return Err(BorzError::Rejected(String::from("Unsuccessful login")));
+    }
+    let token = token_auth.token.ok_or(BorzError::MissingData("token"))?;
+    let refresh_token = token_auth
+        .refresh_token
+        .ok_or(BorzError::MissingData("refreshToken"))?;
+    let user = token_auth.user.ok_or(BorzError::MissingData("user"))?;
+    config.profiles.insert(
+        String::from(profile),
+        Profile {
+            name: String::from(profile),
+            token,
+            refresh_token,
+            username: user.username.clone(),
+            server: server.to_string(),
+        },
+    );
+    // the first account logged in to becomes the one used by default
+    if config.current.is_none() {
+        config.current = Some(String::from(profile));
+    }
+    config.save()?;
+    let mut document = JsonValue::new_object();
+    document["profile"] = profile.into();
+    document["username"] = user.username.into();
+    document["server"] = server.to_string().into();
+    out.success(document, "You have successfully logged in!");
+    Ok(())
 }
 
-fn main() {
-    let args = args::parse_args();
-    let config = load_config();
-    match args.subcommand_name() {
-        None => launch_app(args, config),
-        Some(name) => match name {
-            "clean" => {
-                fs::remove_file(CONFIG_FILE_PATH.clone()).unwrap();
-                fs::remove_dir(CONFIG_FOLDER_PATH.clone()).unwrap();
-                println!("Successfully removed all cached and config data.");
-            }
-            "login" => {
-                println!("Enter the address of the server:");
-                let server = Url::parse(&read_line()[..]).unwrap();
-                println!("Enter your username:");
-                let username = read_line();
-                println!("Enter your password:");
-                let password: String = read_password().unwrap();
-                if username.is_empty() || password.is_empty() {
-                    println!("Username and password cannot be empty!");
-                    return;
-                }
-                let mut fetcher = api::fetch::APIFetcher::new(server.clone(), TOP_LEVEL_ID.clone());
-                let res = fetcher.mutate_auth(username, password);
-                let data = res.data.unwrap();
-                let token_auth = data.token_auth.unwrap();
-                if !token_auth.success.unwrap() {
-                    println!("Unsuccessful login");
-                    return;
-                }
-                let token = token_auth.token.unwrap();
-                let refresh_token = token_auth.refresh_token.unwrap();
-                fs::write(
-                        CONFIG_FILE_PATH.clone(),
-                        format!(
-                            "{{\"token\": \"{}\", \"refresh_token\": \"{}\", \"username\": \"{}\", \"server\": \"{}\"}}",
-                            token, refresh_token, token_auth.user.unwrap().username, server.into_string(),
-                        )
-                    ).unwrap();
-                println!("You have successfully logged in!");
-            }
-            "logout" => {
-                if Path::new(&CONFIG_FILE_PATH.clone()).is_file() {
-                    fs::write(CONFIG_FILE_PATH.clone(), "{}").unwrap();
-                }
-                println!("Successfully logged out.");
-            }
-            "signup" => {
-                println!("Enter the address of the server:");
-                let server = Url::parse(&read_line()[..]).unwrap();
-                println!("Enter your email:");
-                let email = read_line();
-                println!("Enter a username:");
-                let username = read_line();
-                println!("Enter a password:");
-                let password: String = read_password().unwrap();
-                println!("Re-enter your password:");
-                let check: String = read_password().unwrap();
-                if password != check {
-                    println!("Entered passwords must match!");
-                    return;
-                }
-                let fetcher = api::fetch::APIFetcher::new(server, TOP_LEVEL_ID.clone());
-                let res = fetcher.mutate_register(email, username, password);
-                if !res.data.unwrap().register.unwrap().success.unwrap() {
-                    println!("Your request was rejected by the server. Please make sure you have a valid username, email, and a strong password.");
-                    return;
+fn signup(out: Output, args: &ArgMatches, overrides: &Overrides) -> Result<(), BorzError> {
+    let server = server_or_prompt(overrides.server.as_deref())?;
+    let email = given_or_prompt(args.value_of("email"), "Enter your email:");
+    let username = given_or_prompt(overrides.username.as_deref(), "Enter a username:");
+    let password = read_secret(overrides, "Enter a password:")?;
+    // a password piped in cannot be mistyped, so it is not asked for twice
+    let check = if overrides.password_stdin {
+        password.clone()
+    } else {
+        read_secret(overrides, "Re-enter your password:")?
+    };
+    if password != check {
+        return Err(BorzError::InvalidInput(String::from(
+            "Entered passwords must match!",
+        )));
+    }
+    let fetcher = api::fetch::APIFetcher::new(server, TOP_LEVEL_ID.clone());
+    let register = fetcher
+        .mutate_register(email.clone(), username.clone(), password)?
+        .register
+        .ok_or(BorzError::MissingData("register"))?;
+    if !register.success.unwrap_or(false) {
+        return Err(BorzError::Rejected(String::from("Your request was rejected by the server. Please make sure you have a valid username, email, and a strong password.")));
+    }
+    let mut document = JsonValue::new_object();
+    document["username"] = username.into();
+    document["email"] = email.clone().into();
+    out.success(
+        document,
+        &format!(
+            "You have successfully signed up for Borz. Please check your email for more instructions. If it does not arrive, run borz verify --resend {}",
+            email
+        ),
+    );
+    // someone at a terminal can have the email sent again without starting over
+    if out == Output::Text && !overrides.password_stdin && io::stdin().is_terminal() {
+        while given_or_prompt(
+            None,
+            "Type resend if the email has not arrived, or press enter to finish:",
+        ) == "resend"
+        {
+            fetcher.mutate_resend_activation_email(email.clone())?;
+            prompt("The activation email has been sent again.");
+        }
+    }
+    Ok(())
+}
+
+fn verify(out: Output, args: &ArgMatches, overrides: &Overrides) -> Result<(), BorzError> {
+    let server = server_or_prompt(overrides.server.as_deref())?;
+    if let Some(email) = args.value_of("resend") {
+        let fetcher = api::fetch::APIFetcher::new(server, TOP_LEVEL_ID.clone());
+        fetcher.mutate_resend_activation_email(String::from(email))?;
+        let mut document = JsonValue::new_object();
+        document["email"] = email.into();
+        out.success(
+            document,
+            "The activation email has been sent again. Run borz verify with the key from it",
+        );
+        return Ok(());
+    }
+    let key = given_or_prompt(args.value_of("KEY"), "Enter the key from your email:");
+    let fetcher = api::fetch::APIFetcher::new(server, TOP_LEVEL_ID.clone());
+    let verify_account = fetcher
+        .mutate_verify(key)?
+        .verify_account
+        .ok_or(BorzError::MissingData("verifyAccount"))?;
+    if !verify_account.success.unwrap_or(false) {
+        return Err(BorzError::Rejected(String::from(
+            "Your token was incorrect. To get a new one, run borz verify --resend <email>",
+        )));
+    }
+    out.success(
+        JsonValue::new_object(),
+        "You have successfully created an account. You may now log in by running borz login",
+    );
+    Ok(())
+}
+
+fn clean(out: Output) -> Result<(), BorzError> {
+    // the folder also holds the backup of a corrupt config and any temporary file left by a save,
+    // and there is nothing to clean on a fresh install
+    match fs::remove_dir_all(CONFIG_FOLDER_PATH.clone()) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+        _ => {}
+    }
+    out.success(
+        JsonValue::new_object(),
+        "Successfully removed all cached and config data.",
+    );
+    Ok(())
+}
+
+// revokes the refresh token on the server so it stops working even if a copy of the config survives
+fn revoke(profile: &Profile) -> Result<(), BorzError> {
+    let server = Url::parse(&profile.server)?;
+    let fetcher = api::fetch::APIFetcher::new(server, TOP_LEVEL_ID.clone());
+    fetcher.mutate_revoke_token(profile.refresh_token.clone())
+}
+
+fn logout(
+    out: Output,
+    config: &mut Config,
+    profile: &str,
+    session: Option<&Profile>,
+    args: &ArgMatches,
+) -> Result<(), BorzError> {
+    let names: Vec<String> = if args.is_present("all-profiles") {
+        config.profiles.keys().cloned().collect()
+    } else if config.profile(profile).is_none() {
+        // a session given through the environment has nothing stored to forget
+        return match session {
+            Some(session) => logout_environment(out, session),
+            None => Err(BorzError::NotLoggedIn),
+        };
+    } else {
+        vec![String::from(profile)]
+    };
+    let mut document = JsonValue::new_object();
+    document["profiles"] = JsonValue::new_array();
+    let mut lines = Vec::new();
+    for name in names {
+        let mut entry = JsonValue::new_object();
+        entry["profile"] = name.clone().into();
+        entry["revoked"] = false.into();
+        // the local credentials are forgotten even if the server cannot be reached
+        if let Some(stored) = config.profile(&name) {
+            match revoke(&stored) {
+                Ok(()) => {
+                    entry["revoked"] = true.into();
+                    lines.push(format!("Logged out of {}.", name));
                 }
-                println!("You have successfully signed up for Borz. Please check your email for more instructions");
-            }
-            "verify" => {
-                println!("Enter the address of the server:");
-                let server = Url::parse(&read_line()[..]).unwrap();
-                println!("Enter the key from your email:");
-                let key = read_line();
-                let fetcher = api::fetch::APIFetcher::new(server, TOP_LEVEL_ID.clone());
-                let res = fetcher.mutate_verify(key);
-                if !res.data.unwrap().verify_account.unwrap().success.unwrap() {
-                    println!("Your token was incorrect");
-                    return;
+                Err(e) => {
+                    entry["error"] = e.to_string().into();
+                    lines.push(format!(
+                        "Logged out of {}, but its session could not be revoked on the server: {}",
+                        name, e
+                    ));
                 }
-                println!("You have successfully created an account. You may now log in by running borz login");
             }
+            config.profiles.remove(&name);
+        }
+        if config.current.as_deref() == Some(&name[..]) {
+            config.current = None;
+        }
+        let _ = document["profiles"].push(entry);
+    }
+    config.save()?;
+    if lines.is_empty() {
+        lines.push(String::from("Successfully logged out."));
+    }
+    out.success(document, &lines.join("\n"));
+    Ok(())
+}
+
+fn logout_environment(out: Output, session: &Profile) -> Result<(), BorzError> {
+    if session.refresh_token.is_empty() {
+        return Err(BorzError::InvalidInput(String::from(
+            "Nothing was revoked, as BORZ_TOKEN was given without BORZ_REFRESH_TOKEN. Unset BORZ_TOKEN to stop using it",
+        )));
+    }
+    revoke(session)?;
+    let mut document = JsonValue::new_object();
+    document["profiles"] = JsonValue::new_array();
+    let mut entry = JsonValue::new_object();
+    entry["profile"] = JsonValue::Null;
+    entry["revoked"] = true.into();
+    let _ = document["profiles"].push(entry);
+    out.success(
+        document,
+        "Revoked the session from BORZ_REFRESH_TOKEN. Unset BORZ_TOKEN and BORZ_REFRESH_TOKEN to stop using it.",
+    );
+    Ok(())
+}
+
+fn main() {
+    let args = args::parse_args();
+    let out = Output::from_name(args.value_of("output").unwrap_or("text"));
+    let mut config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            out.failure(&e);
+            process::exit(e.exit_code());
+        }
+    };
+    let overrides = Overrides::new(
+        args.value_of("server"),
+        args.value_of("username"),
+        args.is_present("password-stdin"),
+    );
+    let profile_name = config.selected(args.value_of("profile"));
+    let profile = config.session(&profile_name, &overrides);
+    let res = match args.subcommand_name() {
+        None => launch_app(profile),
+        Some(name) => match name {
+            "clean" => clean(out),
+            "login" => login(out, &mut config, &profile_name, &overrides),
+            "logout" => logout(
+                out,
+                &mut config,
+                &profile_name,
+                profile.as_ref(),
+                args.subcommand_matches("logout").unwrap(),
+            ),
+            "signup" => signup(out, args.subcommand_matches("signup").unwrap(), &overrides),
+            "verify" => verify(out, args.subcommand_matches("verify").unwrap(), &overrides),
+            "whoami" => commands::whoami::whoami(profile.as_ref(), out),
+            "ls" => commands::ls::ls(
+                profile.as_ref(),
+                args.subcommand_matches("ls").unwrap(),
+                out,
+            ),
+            "cat" => commands::cat::cat(
+                profile.as_ref(),
+                args.subcommand_matches("cat").unwrap(),
+                out,
+            ),
+            "group" => commands::group::group(
+                profile.as_ref(),
+                args.subcommand_matches("group").unwrap(),
+                out,
+            ),
+            "post" => commands::post::post(
+                profile.as_ref(),
+                args.subcommand_matches("post").unwrap(),
+                out,
+            ),
+            "reply" => commands::reply::reply(
+                profile.as_ref(),
+                args.subcommand_matches("reply").unwrap(),
+                out,
+            ),
+            "account" => commands::account::account(
+                &mut config,
+                profile.as_ref(),
+                args.subcommand_matches("account").unwrap(),
+                &overrides,
+                out,
+            ),
+            "email" => commands::email::email(
+                profile.as_ref(),
+                args.subcommand_matches("email").unwrap(),
+                &overrides,
+                out,
+            ),
+            "password" => commands::password::password(
+                profile.as_ref(),
+                args.subcommand_matches("password").unwrap(),
+                &overrides,
+                out,
+            ),
+            "profile" => commands::profile::profile(
+                &mut config,
+                args.subcommand_matches("profile").unwrap(),
+                out,
+            ),
             _ => panic!("Unknown argument"),
         },
+    };
+    if let Err(e) = res {
+        out.failure(&e);
+        process::exit(e.exit_code());
     }
 }
@@ -0,0 +1,72 @@
+use crate::api::error::BorzError;
+use graphql_client::PathFragment;
+use json::JsonValue;
+
+// how subcommands report their results, chosen with the global --output flag
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Output {
+    Text,
+    Json,
+}
+
+impl Output {
+    pub fn from_name(name: &str) -> Output {
+        match name {
+            "json" => Output::Json,
+            _ => Output::Text,
+        }
+    }
+
+    // prints the prose for people, or the document with "ok" set for scripts
+    pub fn success(self, mut document: JsonValue, text: &str) {
+        match self {
+            Output::Text => {
+                if !text.is_empty() {
+                    println!("{}", text);
+                }
+            }
+            Output::Json => {
+                document["ok"] = true.into();
+                println!("{}", document.pretty(2));
+            }
+        }
+    }
+
+    // prose goes to stderr so it never ends up in a pipeline, while scripts read the document from stdout
+    pub fn failure(self, error: &BorzError) {
+        match self {
+            Output::Text => eprintln!("{}", error),
+            Output::Json => {
+                let mut document = JsonValue::new_object();
+                document["ok"] = false.into();
+                document["error"] = error_document(error);
+                println!("{}", document.pretty(2));
+            }
+        }
+    }
+}
+
+fn error_document(error: &BorzError) -> JsonValue {
+    let mut document = JsonValue::new_object();
+    document["kind"] = error.kind().into();
+    document["message"] = error.to_string().into();
+    document["exit_code"] = error.exit_code().into();
+    let mut details = JsonValue::new_array();
+    if let BorzError::GraphQL(errors) = error {
+        for e in errors {
+            let mut detail = JsonValue::new_object();
+            detail["message"] = e.message.clone().into();
+            let mut path = JsonValue::new_array();
+            for fragment in e.path.iter().flatten() {
+                let _ = match fragment {
+                    PathFragment::Key(key) => path.push(key.clone()),
+                    PathFragment::Index(index) => path.push(*index),
+                };
+            }
+            detail["path"] = path;
+            let _ = details.push(detail);
+        }
+    }
+    document["details"] = details;
+    document
+}
@@ -1,10 +1,51 @@
 use crate::app::{App, AppPage};
 use tui::backend::Backend;
-use tui::layout::{Constraint, Layout};
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Style};
+use tui::terminal::Frame;
+use tui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
 use tui::Terminal;
 
+pub fn popup_area(area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage(35),
+                Constraint::Percentage(30),
+                Constraint::Percentage(35),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage(20),
+                Constraint::Percentage(60),
+                Constraint::Percentage(20),
+            ]
+            .as_ref(),
+        )
+        .split(vertical[1])[1]
+}
+
+fn draw_error<B: Backend>(f: &mut Frame<B>, error: &Option<String>) {
+    if let Some(message) = error {
+        let area = popup_area(f.size());
+        let paragraph = Paragraph::new(format!("{}\n\nPress any key to continue", message))
+            .block(Block::default().title("Error").borders(Borders::ALL))
+            .style(Style::default().bg(Color::Red).fg(Color::White))
+            .wrap(Wrap { trim: true });
+        f.render_widget(Clear, area);
+        f.render_widget(paragraph, area);
+    }
+}
+
 pub fn draw<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) {
-    match app.get_page().as_mut().unwrap() {
+    let error = app.get_error();
+    match app.get_page().unwrap() {
         AppPage::Group(gp) => {
             gp.resize(&terminal.get_frame().size());
             terminal
@@ -12,7 +53,8 @@ pub fn draw<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) {
                     let chunk = Layout::default()
                         .constraints(vec![Constraint::Percentage(100)])
                         .split(f.size())[0];
-                    f.render_widget(gp.clone(), chunk);
+                    f.render_widget(gp.as_ref().clone(), chunk);
+                    draw_error(f, &error);
                 })
                 .unwrap();
         }
@@ -24,6 +66,19 @@ pub fn draw<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) {
                         .constraints(vec![Constraint::Percentage(100)])
                         .split(f.size())[0];
                     f.render_widget(up.clone(), chunk);
+                    draw_error(f, &error);
+                })
+                .unwrap();
+        }
+        AppPage::Loading(lp) => {
+            lp.resize(&terminal.get_frame().size());
+            terminal
+                .draw(|f| {
+                    let chunk = Layout::default()
+                        .constraints(vec![Constraint::Percentage(100)])
+                        .split(f.size())[0];
+                    f.render_widget(lp.clone(), chunk);
+                    draw_error(f, &error);
                 })
                 .unwrap();
         }
@@ -34,7 +89,8 @@ pub fn draw<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) {
                     let chunk = Layout::default()
                         .constraints(vec![Constraint::Percentage(100)])
                         .split(f.size())[0];
-                    f.render_widget(tp.clone(), chunk);
+                    f.render_widget(tp.as_ref().clone(), chunk);
+                    draw_error(f, &error);
                 })
                 .unwrap();
         }
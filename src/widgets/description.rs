@@ -0,0 +1,109 @@
+use crate::app::{App, AppPage};
+use crate::widgets::editor::Editor;
+use crossterm::event::{KeyCode, KeyEvent};
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::style::{Color, Style};
+use tui::text::Text;
+use tui::widgets::{Block, Borders, Paragraph, Widget, Wrap};
+
+#[derive(Debug, Clone)]
+pub struct DescriptionWidget {
+    description: String,
+    // the edited description, kept until the server accepts it
+    draft: Option<Editor>,
+    focused: bool,
+}
+
+impl DescriptionWidget {
+    pub fn new(description: String) -> DescriptionWidget {
+        DescriptionWidget {
+            description,
+            draft: None,
+            focused: false,
+        }
+    }
+
+    pub fn focus(&mut self) {
+        self.focused = true;
+    }
+
+    pub fn unfocus(&mut self) {
+        self.focused = false;
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.draft.is_some()
+    }
+
+    pub fn set_description(&mut self, description: String) {
+        self.description = description;
+        self.draft = None;
+    }
+
+    pub fn paste(&mut self, text: &str) {
+        if let Some(draft) = &mut self.draft {
+            draft.paste(text);
+        }
+    }
+
+    pub fn resize(&mut self, area: &Rect) {
+        if let Some(draft) = &mut self.draft {
+            draft.resize(area.width as usize - 2, area.height as usize - 2);
+        }
+    }
+
+    pub fn update(&mut self, key: KeyEvent) -> Box<dyn for<'a> Fn(&'a mut App)> {
+        if !self.focused {
+            return Box::new(|_| {});
+        }
+        match (&mut self.draft, key.code) {
+            (None, KeyCode::Char('e')) if key.modifiers.is_empty() => {
+                self.draft = Some(Editor::new(&self.description));
+            }
+            (Some(_), KeyCode::Esc) => {
+                self.draft = None;
+            }
+            (Some(draft), KeyCode::Insert) => {
+                let description = draft.text();
+                return Box::new(move |app| {
+                    if let AppPage::Group(gp) = app.get_page().unwrap() {
+                        let job = gp.save_description(description.clone());
+                        app.spawn(job);
+                    } else {
+                        panic!("Wrong page execution");
+                    }
+                });
+            }
+            (Some(draft), _) => {
+                draft.update(key);
+            }
+            _ => {}
+        }
+        Box::new(|_| {})
+    }
+}
+
+impl Widget for DescriptionWidget {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // the editor wraps its own rows and keeps their leading spaces
+        let (title, text, trim) = match self.draft {
+            Some(draft) => (
+                "Editing Description",
+                Text::from(draft.spans(self.focused)),
+                false,
+            ),
+            None => ("Description", Text::from(self.description), true),
+        };
+        let paragraph = Paragraph::new(text)
+            .block(Block::default().title(title).borders(Borders::ALL).style(
+                Style::default().bg(Color::Green).fg(if self.focused {
+                    Color::Cyan
+                } else {
+                    Color::White
+                }),
+            ))
+            .wrap(Wrap { trim });
+        paragraph.render(area, buf);
+    }
+}
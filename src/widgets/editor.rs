@@ -0,0 +1,544 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::cmp::min;
+use tui::style::{Color, Style};
+use tui::text::{Span, Spans};
+
+use crate::widgets::text::{expand_tabs, graphemes, is_printable, single_line, width, TAB_WIDTH};
+
+// the number of edits that can be undone
+const UNDO_LIMIT: usize = 100;
+
+#[derive(Debug, Clone)]
+struct Snapshot {
+    lines: Vec<Vec<String>>,
+    row: usize,
+    col: usize,
+}
+
+// a row of text on screen: the line it belongs to and the range of graphemes it shows
+#[derive(Debug, Clone, Copy)]
+struct VisualRow {
+    line: usize,
+    start: usize,
+    end: usize,
+    last: bool,
+}
+
+// a text editor which the message, description, title and dialog widgets draw inside their blocks
+#[derive(Debug, Clone)]
+pub struct Editor {
+    // each line split into graphemes, which the cursor moves between
+    lines: Vec<Vec<String>>,
+    // fields such as titles hold one line, leaving enter and tab to the widget around them
+    multiline: bool,
+    row: usize,
+    col: usize,
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    // whether the last edit was typing, so a run of characters is undone at once
+    typing: bool,
+    width: usize,
+    height: usize,
+    scroll_top: usize,
+}
+
+impl Editor {
+    pub fn new(text: &str) -> Editor {
+        Editor {
+            lines: expand_tabs(text).split('\n').map(graphemes).collect(),
+            multiline: true,
+            row: 0,
+            col: 0,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            typing: false,
+            width: 0,
+            height: 0,
+            scroll_top: 0,
+        }
+    }
+
+    pub fn one_line(text: &str) -> Editor {
+        Editor {
+            multiline: false,
+            ..Editor::new(&single_line(text))
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| line.concat())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    // the space inside the block the editor is drawn in
+    pub fn resize(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+        let rows = self.rows();
+        let cursor = self.cursor_row(&rows);
+        if self.scroll_top > cursor {
+            self.scroll_top = cursor;
+        } else if self.height > 0 && cursor >= self.scroll_top + self.height {
+            self.scroll_top = cursor + 1 - self.height;
+        }
+        self.scroll_top = min(self.scroll_top, rows.len() - 1);
+    }
+
+    pub fn update(&mut self, key: KeyEvent) {
+        if key.modifiers == KeyModifiers::CONTROL {
+            match key.code {
+                KeyCode::Char('z') => self.undo(),
+                KeyCode::Char('y') => self.redo(),
+                KeyCode::Left => self.word_left(),
+                KeyCode::Right => self.word_right(),
+                KeyCode::Home => self.move_to(0, 0),
+                KeyCode::End => {
+                    let row = self.lines.len() - 1;
+                    self.move_to(row, self.lines[row].len());
+                }
+                _ => {}
+            }
+        } else if key.modifiers.is_empty() || key.modifiers == KeyModifiers::SHIFT {
+            match key.code {
+                KeyCode::Char(c) if is_printable(c) => {
+                    // a space ends the word being typed, so each word is undone separately
+                    self.checkpoint(c != ' ');
+                    self.insert(&c.to_string());
+                }
+                KeyCode::Tab if self.multiline => {
+                    // tabs are typed as spaces up to the next tab stop
+                    let used = width(&self.lines[self.row][..self.col].concat());
+                    self.checkpoint(false);
+                    self.insert(&" ".repeat(TAB_WIDTH - used % TAB_WIDTH));
+                }
+                KeyCode::Enter if self.multiline => self.newline(),
+                KeyCode::Backspace => self.backspace(),
+                KeyCode::Delete => self.delete(),
+                KeyCode::Left => self.left(),
+                KeyCode::Right => self.right(),
+                KeyCode::Up => self.vertical(false),
+                KeyCode::Down => self.vertical(true),
+                KeyCode::Home => self.move_to(self.row, 0),
+                KeyCode::End => self.move_to(self.row, self.lines[self.row].len()),
+                _ => {}
+            }
+        }
+    }
+
+    // the visible rows, with the cursor drawn on one of them when it is shown
+    pub fn spans(&self, show_cursor: bool) -> Vec<Spans<'static>> {
+        let rows = self.rows();
+        let cursor = self.cursor_row(&rows);
+        let bottom = min(rows.len(), self.scroll_top + self.height.max(1));
+        let mut text = vec![];
+        for (pos, row) in rows[self.scroll_top..bottom].iter().enumerate() {
+            let line = &self.lines[row.line];
+            if show_cursor && pos + self.scroll_top == cursor {
+                let before = line[row.start..self.col].concat();
+                let under = line
+                    .get(self.col)
+                    .cloned()
+                    .unwrap_or_else(|| String::from(" "));
+                let after = if self.col < row.end {
+                    line[(self.col + 1)..row.end].concat()
+                } else {
+                    String::new()
+                };
+                text.push(Spans::from(vec![
+                    Span::raw(before),
+                    Span::styled(under, Style::default().bg(Color::Red)),
+                    Span::raw(after),
+                ]));
+            } else {
+                text.push(Spans::from(vec![Span::raw(
+                    line[row.start..row.end].concat(),
+                )]));
+            }
+        }
+        text
+    }
+
+    // splits each line into rows, breaking after the last space that fits where possible
+    fn rows(&self) -> Vec<VisualRow> {
+        // one column is kept free for the cursor at the end of a row
+        let columns = if self.width > 1 {
+            self.width - 1
+        } else {
+            usize::MAX
+        };
+        let mut rows = vec![];
+        for (index, line) in self.lines.iter().enumerate() {
+            let mut start = 0;
+            let mut used = 0;
+            let mut space = None;
+            for (pos, g) in line.iter().enumerate() {
+                if used + width(g) > columns && pos > start {
+                    let end = match space {
+                        Some(space) if space > start => space + 1,
+                        _ => pos,
+                    };
+                    rows.push(VisualRow {
+                        line: index,
+                        start,
+                        end,
+                        last: false,
+                    });
+                    start = end;
+                    used = width(&line[start..pos].concat());
+                    space = None;
+                }
+                if g == " " {
+                    space = Some(pos);
+                }
+                used += width(g);
+            }
+            rows.push(VisualRow {
+                line: index,
+                start,
+                end: line.len(),
+                last: true,
+            });
+        }
+        rows
+    }
+
+    fn cursor_row(&self, rows: &[VisualRow]) -> usize {
+        rows.iter()
+            .position(|row| row.line == self.row && (self.col < row.end || row.last))
+            .unwrap_or(0)
+    }
+
+    fn move_to(&mut self, row: usize, col: usize) {
+        self.row = row;
+        self.col = col;
+        self.typing = false;
+    }
+
+    fn left(&mut self) {
+        if self.col > 0 {
+            self.move_to(self.row, self.col - 1);
+        } else if self.row > 0 {
+            self.move_to(self.row - 1, self.lines[self.row - 1].len());
+        }
+    }
+
+    fn right(&mut self) {
+        if self.col < self.lines[self.row].len() {
+            self.move_to(self.row, self.col + 1);
+        } else if self.row < self.lines.len() - 1 {
+            self.move_to(self.row + 1, 0);
+        }
+    }
+
+    // moves to the start of the word before the cursor
+    fn word_left(&mut self) {
+        if self.col == 0 {
+            return self.left();
+        }
+        let line = &self.lines[self.row];
+        let mut col = self.col;
+        while col > 0 && line[col - 1].trim().is_empty() {
+            col -= 1;
+        }
+        while col > 0 && !line[col - 1].trim().is_empty() {
+            col -= 1;
+        }
+        self.move_to(self.row, col);
+    }
+
+    // moves past the end of the word after the cursor
+    fn word_right(&mut self) {
+        let line = &self.lines[self.row];
+        if self.col == line.len() {
+            return self.right();
+        }
+        let mut col = self.col;
+        while col < line.len() && line[col].trim().is_empty() {
+            col += 1;
+        }
+        while col < line.len() && !line[col].trim().is_empty() {
+            col += 1;
+        }
+        self.move_to(self.row, col);
+    }
+
+    // moves between rows on screen, keeping the cursor's offset into the row
+    fn vertical(&mut self, down: bool) {
+        let rows = self.rows();
+        let current = self.cursor_row(&rows);
+        let target = if down {
+            if current + 1 >= rows.len() {
+                return;
+            }
+            rows[current + 1]
+        } else {
+            if current == 0 {
+                return;
+            }
+            rows[current - 1]
+        };
+        // the cursor keeps its column on screen, which may be a different number of graphemes
+        let line = &self.lines[self.row];
+        let offset = width(&line[rows[current].start..self.col].concat());
+        let target_line = &self.lines[target.line];
+        let mut col = target.start;
+        let mut used = 0;
+        while col < target.end && used + width(&target_line[col]) <= offset {
+            used += width(&target_line[col]);
+            col += 1;
+        }
+        // the end of a wrapped row is the start of the next one
+        let end = if target.last {
+            target.end
+        } else {
+            target.end - 1
+        };
+        self.move_to(target.line, min(col, end));
+    }
+
+    // records the text before an edit so it can be undone
+    fn checkpoint(&mut self, typing: bool) {
+        if !(typing && self.typing) {
+            self.undo.push(Snapshot {
+                lines: self.lines.clone(),
+                row: self.row,
+                col: self.col,
+            });
+            if self.undo.len() > UNDO_LIMIT {
+                self.undo.remove(0);
+            }
+        }
+        self.redo.clear();
+        self.typing = typing;
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) -> Snapshot {
+        let current = Snapshot {
+            lines: std::mem::replace(&mut self.lines, snapshot.lines),
+            row: self.row,
+            col: self.col,
+        };
+        self.move_to(snapshot.row, snapshot.col);
+        current
+    }
+
+    fn undo(&mut self) {
+        if let Some(snapshot) = self.undo.pop() {
+            let current = self.restore(snapshot);
+            self.redo.push(current);
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(snapshot) = self.redo.pop() {
+            let current = self.restore(snapshot);
+            self.undo.push(current);
+        }
+    }
+
+    // inserts text without line breaks at the cursor, which may combine with the grapheme before it
+    fn insert(&mut self, text: &str) {
+        let line = &self.lines[self.row];
+        let before = line[..self.col].concat() + text;
+        let after = line[self.col..].concat();
+        self.col = graphemes(&before).len();
+        self.lines[self.row] = graphemes(&(before + &after));
+    }
+
+    // inserts pasted text at the cursor, undone as a single edit
+    pub fn paste(&mut self, text: &str) {
+        self.checkpoint(false);
+        let text = if self.multiline {
+            expand_tabs(text)
+        } else {
+            single_line(text)
+        };
+        let text: String = text
+            .chars()
+            .filter(|c| *c == '\n' || is_printable(*c))
+            .collect();
+        for (index, line) in text.split('\n').enumerate() {
+            if index > 0 {
+                self.split_line();
+            }
+            self.insert(line);
+        }
+    }
+
+    fn newline(&mut self) {
+        self.checkpoint(false);
+        self.split_line();
+    }
+
+    fn split_line(&mut self) {
+        let rest = self.lines[self.row].split_off(self.col);
+        self.lines.insert(self.row + 1, rest);
+        self.move_to(self.row + 1, 0);
+    }
+
+    // deletes the character before the cursor, joining the line onto the previous one at its start
+    fn backspace(&mut self) {
+        if self.col > 0 {
+            self.checkpoint(false);
+            self.lines[self.row].remove(self.col - 1);
+            self.move_to(self.row, self.col - 1);
+        } else if self.row > 0 {
+            self.checkpoint(false);
+            let line = self.lines.remove(self.row);
+            let col = self.lines[self.row - 1].len();
+            self.lines[self.row - 1].extend(line);
+            self.move_to(self.row - 1, col);
+        }
+    }
+
+    // deletes the character after the cursor, joining the next line on at the end of this one
+    fn delete(&mut self) {
+        if self.col < self.lines[self.row].len() {
+            self.checkpoint(false);
+            self.lines[self.row].remove(self.col);
+        } else if self.row < self.lines.len() - 1 {
+            self.checkpoint(false);
+            let line = self.lines.remove(self.row + 1);
+            self.lines[self.row].extend(line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(editor: &mut Editor, code: KeyCode) {
+        editor.update(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn ctrl(editor: &mut Editor, code: KeyCode) {
+        editor.update(KeyEvent::new(code, KeyModifiers::CONTROL));
+    }
+
+    fn type_text(editor: &mut Editor, text: &str) {
+        for c in text.chars() {
+            press(editor, KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn backspace_joins_a_line_onto_the_previous_one() {
+        let mut editor = Editor::new("ab\ncd");
+        press(&mut editor, KeyCode::Down);
+        press(&mut editor, KeyCode::Home);
+        press(&mut editor, KeyCode::Backspace);
+        assert_eq!(editor.text(), "abcd");
+        assert_eq!((editor.row, editor.col), (0, 2));
+    }
+
+    #[test]
+    fn delete_joins_the_next_line_on() {
+        let mut editor = Editor::new("ab\ncd");
+        press(&mut editor, KeyCode::End);
+        press(&mut editor, KeyCode::Delete);
+        assert_eq!(editor.text(), "abcd");
+        assert_eq!((editor.row, editor.col), (0, 2));
+    }
+
+    #[test]
+    fn backspace_removes_a_whole_grapheme() {
+        let mut editor = Editor::new("cafe\u{301}");
+        press(&mut editor, KeyCode::End);
+        assert_eq!(editor.col, 4);
+        press(&mut editor, KeyCode::Backspace);
+        assert_eq!(editor.text(), "caf");
+    }
+
+    #[test]
+    fn moves_by_word_over_wide_and_combining_graphemes() {
+        let mut editor = Editor::new("日本語 cafe\u{301} x");
+        ctrl(&mut editor, KeyCode::Right);
+        assert_eq!(editor.col, 3);
+        ctrl(&mut editor, KeyCode::Right);
+        assert_eq!(editor.col, 8);
+        ctrl(&mut editor, KeyCode::Left);
+        assert_eq!(editor.col, 4);
+        ctrl(&mut editor, KeyCode::Left);
+        assert_eq!(editor.col, 0);
+    }
+
+    #[test]
+    fn wraps_after_the_last_space_that_fits() {
+        let mut editor = Editor::new("hello world");
+        editor.resize(7, 5);
+        let rows = editor.rows();
+        assert_eq!(rows.len(), 2);
+        assert_eq!((rows[0].start, rows[0].end), (0, 6));
+        assert_eq!((rows[1].start, rows[1].end), (6, 11));
+    }
+
+    #[test]
+    fn wraps_wide_graphemes_by_their_width() {
+        let mut editor = Editor::new("日本語日本語\nok");
+        editor.resize(7, 5);
+        let rows = editor.rows();
+        assert_eq!(rows.len(), 3);
+        assert_eq!((rows[0].start, rows[0].end), (0, 3));
+        assert_eq!((rows[1].start, rows[1].end), (3, 6));
+        assert_eq!(rows[2].line, 1);
+    }
+
+    #[test]
+    fn moves_down_into_a_wrapped_row() {
+        let mut editor = Editor::new("hello world");
+        editor.resize(7, 5);
+        press(&mut editor, KeyCode::Right);
+        press(&mut editor, KeyCode::Down);
+        assert_eq!((editor.row, editor.col), (0, 7));
+    }
+
+    #[test]
+    fn undoes_typing_a_word_at_a_time() {
+        let mut editor = Editor::new("");
+        type_text(&mut editor, "ab c");
+        ctrl(&mut editor, KeyCode::Char('z'));
+        assert_eq!(editor.text(), "ab ");
+        ctrl(&mut editor, KeyCode::Char('z'));
+        assert_eq!(editor.text(), "ab");
+        ctrl(&mut editor, KeyCode::Char('z'));
+        assert_eq!(editor.text(), "");
+        ctrl(&mut editor, KeyCode::Char('y'));
+        assert_eq!(editor.text(), "ab");
+        ctrl(&mut editor, KeyCode::Char('y'));
+        assert_eq!(editor.text(), "ab ");
+    }
+
+    #[test]
+    fn a_new_edit_clears_what_could_be_redone() {
+        let mut editor = Editor::new("");
+        type_text(&mut editor, "ab");
+        ctrl(&mut editor, KeyCode::Char('z'));
+        type_text(&mut editor, "x");
+        ctrl(&mut editor, KeyCode::Char('y'));
+        assert_eq!(editor.text(), "x");
+    }
+
+    #[test]
+    fn undoes_a_paste_at_once() {
+        let mut editor = Editor::new("");
+        editor.paste("one\n\ttwo");
+        assert_eq!(editor.text(), "one\n    two");
+        ctrl(&mut editor, KeyCode::Char('z'));
+        assert_eq!(editor.text(), "");
+    }
+
+    #[test]
+    fn keeps_a_single_line_field_on_one_line() {
+        let mut editor = Editor::one_line("a\nb");
+        assert_eq!(editor.text(), "a b");
+        press(&mut editor, KeyCode::End);
+        press(&mut editor, KeyCode::Enter);
+        press(&mut editor, KeyCode::Tab);
+        editor.paste("c\nd");
+        assert_eq!(editor.text(), "a bc d");
+    }
+}
@@ -9,6 +9,7 @@ use tui::widgets::{Block, Borders, Paragraph, Widget, Wrap};
 pub struct LocationWidget {
     loc: String,
     focused: bool,
+    member: bool,
 }
 
 impl LocationWidget {
@@ -16,9 +17,14 @@ impl LocationWidget {
         LocationWidget {
             loc,
             focused: false,
+            member: false,
         }
     }
 
+    pub fn set_member(&mut self, member: bool) {
+        self.member = member;
+    }
+
     pub fn focus(&mut self) {
         self.focused = true;
     }
@@ -34,7 +40,12 @@ impl LocationWidget {
 
 impl Widget for LocationWidget {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let paragraph = Paragraph::new(self.loc)
+        let loc = if self.member {
+            self.loc + " (member)"
+        } else {
+            self.loc
+        };
+        let paragraph = Paragraph::new(loc)
             .block(
                 Block::default()
                     .title("Location")
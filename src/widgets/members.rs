@@ -0,0 +1,213 @@
+use crate::api::paginate::LOAD_AHEAD;
+use crate::app::{App, AppPage};
+use crate::widgets::page::UserPage;
+use crate::widgets::text::{is_printable, pop_grapheme, single_line};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::cmp::min;
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::style::{Color, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Paragraph, Widget, Wrap};
+
+#[derive(Debug, Clone)]
+pub struct MembersWidget {
+    usernames: Vec<String>,
+    uids: Vec<String>,
+    // the filter the listed members match
+    filter: String,
+    // the filter being typed, kept until it is applied
+    draft: Option<String>,
+    selected_row: usize,
+    focused: bool,
+    scroll_top: usize,
+    scroll_bottom: usize,
+}
+
+impl MembersWidget {
+    pub fn new(usernames: Vec<String>, uids: Vec<String>) -> MembersWidget {
+        MembersWidget {
+            usernames,
+            uids,
+            filter: String::new(),
+            draft: None,
+            selected_row: 0,
+            focused: false,
+            scroll_top: 0,
+            scroll_bottom: 0,
+        }
+    }
+
+    pub fn extend(&mut self, usernames: Vec<String>, uids: Vec<String>) {
+        self.usernames.extend(usernames);
+        self.uids.extend(uids);
+    }
+
+    // replaces the listed members with those matching a new filter
+    pub fn set_filter(&mut self, filter: String) {
+        self.filter = filter;
+        self.usernames.clear();
+        self.uids.clear();
+        self.selected_row = 0;
+        self.scroll_top = 0;
+        self.scroll_bottom = 0;
+    }
+
+    pub fn filter(&self) -> String {
+        self.filter.clone()
+    }
+
+    pub fn is_filtering(&self) -> bool {
+        self.draft.is_some()
+    }
+
+    pub fn focus(&mut self) {
+        self.focused = true;
+    }
+
+    pub fn unfocus(&mut self) {
+        self.focused = false;
+    }
+
+    pub fn resize(&mut self, area: &Rect) {
+        if area.height as usize - 2 == self.scroll_bottom - self.scroll_top {
+            return;
+        }
+        self.scroll_top = 0;
+        self.scroll_bottom = min(self.usernames.len(), area.height as usize - 2);
+        self.scroll();
+    }
+
+    pub fn scroll(&mut self) {
+        if self.scroll_top > self.selected_row {
+            let amt = self.scroll_top - self.selected_row;
+            self.scroll_bottom -= amt;
+            self.scroll_top -= amt;
+        } else if self.scroll_bottom <= self.selected_row {
+            let amt = self.selected_row - self.scroll_bottom + 1;
+            self.scroll_top += amt;
+            self.scroll_bottom += amt;
+        }
+    }
+
+    pub fn paste(&mut self, text: &str) {
+        if let Some(draft) = &mut self.draft {
+            draft.push_str(&single_line(text));
+        }
+    }
+
+    pub fn update(&mut self, key: KeyEvent) -> Box<dyn for<'a> Fn(&'a mut App)> {
+        if !self.focused {
+            return Box::new(|_| {});
+        }
+        if !key.modifiers.is_empty() && key.modifiers != KeyModifiers::SHIFT {
+            return Box::new(|_| {});
+        }
+        if let Some(draft) = &mut self.draft {
+            match key.code {
+                KeyCode::Esc => {
+                    self.draft = None;
+                }
+                KeyCode::Char(c) if is_printable(c) => {
+                    draft.push(c);
+                }
+                KeyCode::Backspace => {
+                    pop_grapheme(draft);
+                }
+                KeyCode::Enter => {
+                    let filter = draft.clone();
+                    self.draft = None;
+                    return Box::new(move |app| {
+                        if let AppPage::Group(gp) = app.get_page().unwrap() {
+                            let job = gp.filter_members(filter.clone());
+                            app.spawn(job);
+                        } else {
+                            panic!("Wrong page execution");
+                        }
+                    });
+                }
+                _ => {}
+            }
+            return Box::new(|_| {});
+        }
+        match key.code {
+            KeyCode::Char('/') => {
+                self.draft = Some(self.filter.clone());
+            }
+            KeyCode::Down
+                if !self.usernames.is_empty() && self.selected_row < self.usernames.len() - 1 =>
+            {
+                self.selected_row += 1;
+                self.scroll();
+                if self.selected_row + LOAD_AHEAD >= self.usernames.len() {
+                    return Box::new(|app| {
+                        if let AppPage::Group(gp) = app.get_page().unwrap() {
+                            if let Some(job) = gp.load_more_members() {
+                                app.spawn(job);
+                            }
+                        } else {
+                            panic!("Wrong page execution");
+                        }
+                    });
+                }
+            }
+            KeyCode::Up if self.selected_row > 0 => {
+                self.selected_row -= 1;
+                self.scroll();
+            }
+            KeyCode::Enter if !self.usernames.is_empty() => {
+                let uid = self.uids[self.selected_row].clone();
+                return Box::new(move |app| {
+                    if let AppPage::Group(gp) = app.get_page().unwrap() {
+                        let fetcher = gp.fetcher.clone();
+                        let uid = uid.clone();
+                        app.load_page(String::from("Loading user"), false, move || {
+                            UserPage::new(fetcher, uid).map(AppPage::User)
+                        });
+                    } else {
+                        panic!("Wrong page execution");
+                    }
+                });
+            }
+            _ => {}
+        }
+        Box::new(|_| {})
+    }
+}
+
+impl Widget for MembersWidget {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut text = vec![];
+        if !self.usernames.is_empty() {
+            for (pos, username) in self.usernames[self.scroll_top..self.scroll_bottom]
+                .iter()
+                .enumerate()
+            {
+                let span = Spans::from(vec![Span::styled(
+                    username.clone(),
+                    if self.focused && pos + self.scroll_top == self.selected_row {
+                        Style::default().bg(Color::Red)
+                    } else {
+                        Style::default()
+                    },
+                )]);
+                text.push(span);
+            }
+        }
+        let title = match (self.draft, self.filter.is_empty()) {
+            (Some(draft), _) => format!("Members matching: {}_", draft),
+            (None, true) => String::from("Members"),
+            (None, false) => format!("Members matching: {}", self.filter),
+        };
+        let paragraph = Paragraph::new(text)
+            .block(Block::default().title(title).borders(Borders::ALL).style(
+                Style::default().bg(Color::Green).fg(if self.focused {
+                    Color::Cyan
+                } else {
+                    Color::White
+                }),
+            ))
+            .wrap(Wrap { trim: true });
+        paragraph.render(area, buf);
+    }
+}
@@ -1,6 +1,8 @@
+use crate::api::fetch::user_key;
+use crate::api::paginate::LOAD_AHEAD;
 use crate::app::{App, AppPage};
-use crate::widgets::page::ThreadPage;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crate::widgets::editor::Editor;
+use crossterm::event::{KeyCode, KeyEvent};
 use std::cmp::min;
 use tui::buffer::Buffer;
 use tui::layout::Rect;
@@ -11,9 +13,14 @@ use tui::widgets::{Block, Borders, Paragraph, Widget, Wrap};
 #[derive(Debug, Clone)]
 pub struct MessagesWidget {
     author: String,
+    // the id of the signed in user, who may edit their own messages
+    user_id: String,
     authors: Vec<String>,
+    author_ids: Vec<String>,
     messages: Vec<Vec<String>>,
-    input_buffer: Vec<Vec<char>>,
+    editor: Editor,
+    // the reply being written, set aside while an earlier message is edited
+    draft: Option<Editor>,
     selected_message: usize,
     selected_row: usize,
     focused: bool,
@@ -21,20 +28,28 @@ pub struct MessagesWidget {
     scroll_top: usize,
     scroll_bottom: usize,
     new: bool,
+    editing_message: Option<usize>,
+    // whether the thread has replies that have not been loaded yet
+    more: bool,
 }
 
 impl MessagesWidget {
     pub fn new(
         authors: Vec<String>,
+        author_ids: Vec<String>,
         messages: Vec<Vec<String>>,
         author: String,
+        user_id: String,
         new: bool,
     ) -> MessagesWidget {
         MessagesWidget {
             author,
+            user_id,
             authors,
+            author_ids,
             messages,
-            input_buffer: Vec::new(),
+            editor: Editor::new(""),
+            draft: None,
             selected_message: 0,
             selected_row: 0,
             focused: false,
@@ -42,9 +57,24 @@ impl MessagesWidget {
             scroll_top: 0,
             scroll_bottom: 0,
             new,
+            editing_message: None,
+            more: false,
         }
     }
 
+    pub fn extend(
+        &mut self,
+        authors: Vec<String>,
+        author_ids: Vec<String>,
+        messages: Vec<Vec<String>>,
+        more: bool,
+    ) {
+        self.authors.extend(authors);
+        self.author_ids.extend(author_ids);
+        self.messages.extend(messages);
+        self.more = more;
+    }
+
     pub fn focus(&mut self) {
         self.focused = true;
     }
@@ -53,29 +83,44 @@ impl MessagesWidget {
         self.focused = false;
     }
 
+    pub fn take_draft(&mut self) -> Editor {
+        self.draft.take().unwrap_or_else(|| Editor::new(""))
+    }
+
+    pub fn restore_draft(&mut self, draft: Editor) {
+        self.editor = draft;
+    }
+
+    fn is_own(&self, index: usize) -> bool {
+        let user = user_key(&self.user_id);
+        user.is_some() && user_key(&self.author_ids[index]) == user
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.editing
+    }
+
+    pub fn paste(&mut self, text: &str) {
+        if self.focused && self.editing {
+            self.editor.paste(text);
+        }
+    }
+
     pub fn resize(&mut self, area: &Rect) {
+        // the author and message labels take two rows inside the border
+        if self.editing {
+            self.editor
+                .resize(area.width as usize - 2, area.height as usize - 4);
+            return;
+        }
         if area.height as usize - 4 != self.scroll_bottom - self.scroll_top {
             self.scroll_top = 0;
             self.scroll_bottom = min(
-                {
-                    if self.editing {
-                        self.input_buffer.len()
-                    } else {
-                        self.messages[self.selected_message].len()
-                    }
-                },
+                self.messages[self.selected_message].len(),
                 area.height as usize - 4,
             );
         }
-        if self.input_buffer.is_empty() {
-            self.input_buffer.push(Vec::new());
-        }
         self.scroll();
-        if self.editing {
-            for index in self.scroll_top..self.scroll_bottom {
-                self.input_buffer[index].truncate(area.width as usize - 2);
-            }
-        }
     }
 
     pub fn scroll(&mut self) {
@@ -94,166 +139,109 @@ impl MessagesWidget {
         if !self.focused {
             return Box::new(|_| {});
         }
+        if self.editing {
+            return self.update_editor(key);
+        }
         if key.modifiers.is_empty() {
             match key.code {
                 KeyCode::Down => {
-                    if self.editing {
-                        if self.input_buffer.is_empty() {
-                            self.input_buffer.push(Vec::new());
-                        }
-                        if self.selected_row < self.input_buffer.len() - 1 {
-                            self.selected_row += 1;
-                        }
-                    } else {
-                        if self.selected_row < self.messages[self.selected_message].len() - 1 {
-                            self.selected_row += 1;
-                        }
+                    if self.selected_row < self.messages[self.selected_message].len() - 1 {
+                        self.selected_row += 1;
                     }
                     self.scroll();
                 }
                 KeyCode::Up => {
-                    if self.editing {
-                        if self.input_buffer.is_empty() {
-                            self.input_buffer.push(Vec::new());
-                        }
-                        if self.selected_row > 0 {
-                            self.selected_row -= 1;
-                        }
-                    } else {
-                        if self.selected_row > 0 {
-                            self.selected_row -= 1;
-                        }
+                    if self.selected_row > 0 {
+                        self.selected_row -= 1;
                     }
                     self.scroll();
                 }
-                KeyCode::Left => {
-                    if self.new {
-                        return Box::new(|_| {});
-                    }
-                    if self.editing {
-                        if self.input_buffer.is_empty() {
-                            self.input_buffer.push(Vec::new());
-                        }
-                        self.editing = false;
-                        self.selected_message = self.messages.len() - 1;
+                KeyCode::Left if self.selected_message > 0 => {
+                    self.selected_message -= 1;
+                    self.selected_row = 0;
+                    self.scroll_top = 0;
+                    self.scroll_bottom = 0;
+                }
+                KeyCode::Right => {
+                    if self.selected_message == self.messages.len() - 1 && !self.more {
+                        self.editing = true;
+                    } else if self.selected_message < self.messages.len() - 1 {
+                        self.selected_message += 1;
                         self.selected_row = 0;
                         self.scroll_top = 0;
                         self.scroll_bottom = 0;
-                    } else {
-                        if self.selected_message > 0 {
-                            self.selected_message -= 1;
-                            self.selected_row = 0;
-                            self.scroll_top = 0;
-                            self.scroll_bottom = 0;
-                        }
                     }
-                }
-                KeyCode::Right => {
-                    if !self.editing {
-                        if self.selected_message == self.messages.len() - 1 {
-                            self.editing = true;
-                            self.selected_row = 0;
-                            self.scroll_top = 0;
-                            self.scroll_bottom = 0;
-                        } else if self.selected_message < self.messages.len() - 1 {
-                            self.selected_message += 1;
-                            self.selected_row = 0;
-                            self.scroll_top = 0;
-                            self.scroll_bottom = 0;
-                        }
+                    if self.more && self.selected_message + LOAD_AHEAD >= self.messages.len() {
+                        return Box::new(|app| {
+                            if let AppPage::Thread(tp) = app.get_page().unwrap() {
+                                if let Some(job) = tp.load_more_replies() {
+                                    app.spawn(job);
+                                }
+                            } else {
+                                panic!("Wrong page execution");
+                            }
+                        });
                     }
                 }
-                KeyCode::Enter => {
-                    if self.editing {
-                        if self.input_buffer.is_empty() {
-                            self.input_buffer.push(Vec::new());
-                        }
-                        if self.selected_row == self.input_buffer.len() - 1 {
-                            self.input_buffer.push(Vec::new());
-                            self.selected_row += 1;
-                            self.scroll();
-                        } else {
-                            self.selected_row += 1;
-                            self.input_buffer.insert(self.selected_row, Vec::new());
-                            self.scroll_bottom -= 1;
-                            self.scroll();
-                        }
-                    }
+                KeyCode::Char('e') if self.is_own(self.selected_message) => {
+                    let editor = Editor::new(&self.messages[self.selected_message].join("\n"));
+                    self.draft = Some(std::mem::replace(&mut self.editor, editor));
+                    self.editing = true;
+                    self.editing_message = Some(self.selected_message);
                 }
-                KeyCode::Backspace => {
-                    if self.editing {
-                        if self.input_buffer.is_empty() {
-                            self.input_buffer.push(Vec::new());
-                        }
-                        self.input_buffer.remove(self.selected_row);
-                        if self.input_buffer.is_empty() {
-                            self.input_buffer.push(Vec::new());
-                        }
-                        if self.selected_row >= self.input_buffer.len() - 1 && self.selected_row > 0
-                        {
-                            self.selected_row -= 1;
-                        }
-                        self.scroll_bottom = min(self.scroll_bottom, self.input_buffer.len());
+                _ => {}
+            }
+        }
+        Box::new(|_| {})
+    }
+
+    fn update_editor(&mut self, key: KeyEvent) -> Box<dyn for<'a> Fn(&'a mut App)> {
+        if key.modifiers.is_empty() {
+            match key.code {
+                KeyCode::Esc if !self.new => {
+                    self.editing = false;
+                    if let Some(index) = self.editing_message.take() {
+                        // discard the edit and go back to the original message
+                        self.editor = self.draft.take().unwrap_or_else(|| Editor::new(""));
+                        self.selected_message = index;
+                    } else {
+                        self.selected_message = self.messages.len() - 1;
                     }
+                    self.selected_row = 0;
+                    self.scroll_top = 0;
+                    self.scroll_bottom = 0;
+                    return Box::new(|_| {});
                 }
                 KeyCode::Insert => {
-                    let mut content = String::new();
-                    for line in self.input_buffer.iter() {
-                        for chr in line.iter() {
-                            content.push(*chr);
-                        }
-                        content.push('\n');
-                    }
-                    content = String::from(content.trim());
+                    let content = String::from(self.editor.text().trim());
                     if !content.is_empty() {
+                        let editing_message = self.editing_message;
+                        let new = self.new;
                         return Box::new(move |app| {
                             if let AppPage::Thread(tp) = app.get_page().unwrap() {
-                                tp.fetcher
-                                    .mutate_thread_reply(tp.thread_id.clone(), content.clone());
-                                let tp = tp.clone();
-                                // reset page
-                                app.pop_page();
-                                app.push_page(AppPage::Thread(ThreadPage::new(
-                                    tp.fetcher.clone(),
-                                    tp.group_path.clone(),
-                                    tp.thread_id.clone(),
-                                    tp.username.clone(),
-                                    false,
-                                )));
+                                let mut tp = tp.clone();
+                                let content = content.clone();
+                                // the thread page is replaced once the message has been sent
+                                app.load_page(String::from("Sending"), true, move || {
+                                    let page = match editing_message {
+                                        Some(index) => tp.update_message(index, content),
+                                        None if new => tp.create_thread(content),
+                                        None => tp.reply(content),
+                                    };
+                                    page.map(|tp| AppPage::Thread(Box::new(tp)))
+                                });
                             } else {
                                 panic!("Wrong page execution");
                             }
                         });
                     }
-                }
-                KeyCode::Char(c) => {
-                    if self.editing {
-                        if c.is_ascii_alphanumeric() || c.is_ascii_punctuation() || c == ' ' {
-                            if self.input_buffer.is_empty() {
-                                self.input_buffer.push(Vec::new());
-                            }
-                            self.input_buffer[self.selected_row].push(c);
-                        }
-                    }
-                }
-                _ => {}
-            }
-        } else if key.modifiers == KeyModifiers::SHIFT {
-            match key.code {
-                KeyCode::Char(c) => {
-                    if self.editing {
-                        if c.is_ascii_alphanumeric() || c.is_ascii_punctuation() || c == ' ' {
-                            if self.input_buffer.is_empty() {
-                                self.input_buffer.push(Vec::new());
-                            }
-                            self.input_buffer[self.selected_row].push(c);
-                        }
-                    }
+                    return Box::new(|_| {});
                 }
                 _ => {}
             }
         }
-        return Box::new(|_| {});
+        self.editor.update(key);
+        Box::new(|_| {})
     }
 }
 
@@ -290,31 +278,23 @@ impl Widget for MessagesWidget {
                 text.push(span);
             }
         } else {
-            for (pos, line) in self.input_buffer[self.scroll_top..self.scroll_bottom]
-                .iter()
-                .enumerate()
-            {
-                let s: String = line.iter().collect();
-                let span = Spans::from(vec![Span::styled(s, {
-                    if self.focused && pos + self.scroll_top == self.selected_row {
-                        Style::default().bg(Color::Red)
-                    } else {
-                        Style::default()
-                    }
-                })]);
-                text.push(span);
-            }
+            text.extend(self.editor.spans(self.focused));
         }
-        let s = format!(
-            "Message {} / {}",
-            1 + self.selected_message,
-            self.messages.len()
-        );
+        let s = match self.editing_message {
+            Some(index) => format!("Editing Message {} / {}", 1 + index, self.messages.len()),
+            None => format!(
+                "Message {} / {}",
+                1 + self.selected_message,
+                self.messages.len()
+            ),
+        };
         let paragraph = Paragraph::new(text)
             .block(
                 Block::default()
                     .title({
-                        if self.editing {
+                        if self.editing && self.new {
+                            "New Thread"
+                        } else if self.editing && self.editing_message.is_none() {
                             "Reply"
                         } else {
                             &s[..]
@@ -327,7 +307,10 @@ impl Widget for MessagesWidget {
             } else {
                 Color::White
             }))
-            .wrap(Wrap { trim: true });
+            // the editor wraps its own rows and keeps their leading spaces
+            .wrap(Wrap {
+                trim: !self.editing,
+            });
         paragraph.render(area, buf);
     }
 }
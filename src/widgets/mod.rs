@@ -1,8 +1,14 @@
 pub mod account;
 pub mod bio;
+pub mod description;
+pub mod editor;
 pub mod location;
+pub mod members;
 pub mod messages;
+pub mod new_subgroup;
 pub mod page;
 pub mod subgroups;
+pub mod text;
 pub mod threads;
+pub mod title;
 pub mod user;
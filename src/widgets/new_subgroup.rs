@@ -0,0 +1,135 @@
+use crate::api::fetch::slugify;
+use crate::app::{App, AppPage};
+use crate::widgets::editor::Editor;
+use crate::widgets::text::width;
+use crossterm::event::{KeyCode, KeyEvent};
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::style::{Color, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap};
+
+const LABELS: [&str; 3] = ["Name", "Slug", "Description"];
+
+// a dialog over the group page for creating a child subgroup
+#[derive(Debug, Clone)]
+pub struct NewSubgroupWidget {
+    parent: String,
+    fields: [Editor; 3],
+    field: usize,
+}
+
+impl NewSubgroupWidget {
+    pub fn new(parent: String) -> NewSubgroupWidget {
+        NewSubgroupWidget {
+            parent,
+            fields: [
+                Editor::one_line(""),
+                Editor::one_line(""),
+                Editor::one_line(""),
+            ],
+            field: 0,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        String::from(self.fields[0].text().trim())
+    }
+
+    // a slug left empty is derived from the name
+    pub fn slug(&self) -> String {
+        match self.fields[1].text().trim() {
+            "" => slugify(&self.fields[0].text()),
+            slug => String::from(slug),
+        }
+    }
+
+    pub fn description(&self) -> String {
+        String::from(self.fields[2].text().trim())
+    }
+
+    pub fn paste(&mut self, text: &str) {
+        self.fields[self.field].paste(text);
+    }
+
+    // each field takes up one row after its label, scrolling to keep the cursor in view
+    pub fn resize(&mut self, area: &Rect) {
+        let labels = LABELS.iter().map(|label| width(label)).max().unwrap_or(0) + 2;
+        for field in self.fields.iter_mut() {
+            field.resize((area.width as usize).saturating_sub(labels + 2), 1);
+        }
+    }
+
+    pub fn update(&mut self, key: KeyEvent) -> Box<dyn for<'a> Fn(&'a mut App)> {
+        if key.modifiers.is_empty() || key.code == KeyCode::BackTab {
+            match key.code {
+                KeyCode::Tab => {
+                    self.field = (self.field + 1) % self.fields.len();
+                    return Box::new(|_| {});
+                }
+                KeyCode::BackTab => {
+                    self.field = (self.field + self.fields.len() - 1) % self.fields.len();
+                    return Box::new(|_| {});
+                }
+                KeyCode::Esc => {
+                    return Box::new(|app| {
+                        if let AppPage::Group(gp) = app.get_page().unwrap() {
+                            gp.close_dialog();
+                        } else {
+                            panic!("Wrong page execution");
+                        }
+                    });
+                }
+                KeyCode::Insert => {
+                    return Box::new(|app| {
+                        if let AppPage::Group(gp) = app.get_page().unwrap() {
+                            match gp.create_subgroup() {
+                                Ok(job) => app.spawn(job),
+                                Err(e) => app.show_error(e),
+                            }
+                        } else {
+                            panic!("Wrong page execution");
+                        }
+                    });
+                }
+                _ => {}
+            }
+        }
+        self.fields[self.field].update(key);
+        Box::new(|_| {})
+    }
+}
+
+impl Widget for NewSubgroupWidget {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut text = vec![];
+        let labels = LABELS.iter().map(|label| width(label)).max().unwrap_or(0);
+        for (pos, label) in LABELS.iter().enumerate() {
+            let mut spans = vec![Span::raw(format!("{:<width$}  ", label, width = labels))];
+            let field = &self.fields[pos];
+            if let Some(row) = field.spans(pos == self.field).pop() {
+                spans.extend(row.0);
+            }
+            // an empty slug shows the one that will be derived from the name
+            if pos == 1 && field.text().trim().is_empty() {
+                spans.push(Span::styled(self.slug(), Style::default().fg(Color::Gray)));
+            }
+            text.push(Spans::from(spans));
+        }
+        text.push(Spans::from(""));
+        text.push(Spans::from(
+            "Tab moves between fields, insert creates the subgroup and escape cancels",
+        ));
+        let paragraph = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title(format!("New Subgroup in {}", self.parent))
+                    .borders(Borders::ALL)
+                    .style(Style::default().bg(Color::Green).fg(Color::Cyan)),
+            )
+            // trimming would drop the spaces typed at the end of a field
+            .wrap(Wrap { trim: false });
+        Clear.render(area, buf);
+        paragraph.render(area, buf);
+    }
+}
@@ -1,41 +1,69 @@
 use crossterm::event::{KeyCode, KeyEvent};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use tui::buffer::Buffer;
 use tui::layout::{Constraint, Direction, Layout, Rect};
-use tui::widgets::Widget;
+use tui::style::{Color, Style};
+use tui::widgets::{Block, Borders, Paragraph, Widget, Wrap};
 
-use crate::api::fetch::APIFetcher;
-use crate::app::{App, AppPage};
+use crate::api::error::BorzError;
+use crate::api::fetch::{APIFetcher, MemberNode, ReplyNode, SubgroupNode, ThreadNode};
+use crate::api::paginate::Paginator;
+use crate::app::{App, AppPage, Job};
+use crate::ui::popup_area;
 use crate::widgets::account::AccountWidget;
+use crate::widgets::description::DescriptionWidget;
 use crate::widgets::location::LocationWidget;
+use crate::widgets::members::MembersWidget;
 use crate::widgets::messages::MessagesWidget;
+use crate::widgets::new_subgroup::NewSubgroupWidget;
 use crate::widgets::subgroups::SubgroupsWidget;
 use crate::widgets::threads::ThreadsWidget;
+use crate::widgets::title::TitleWidget;
 use crate::widgets::user::UserWidget;
 
+// ids for pages loaded on worker threads, unique across pages so that a page which replaced
+// another with the same path ignores the results of its predecessor's requests
+static NEXT_REQUEST: AtomicUsize = AtomicUsize::new(1);
+
+fn next_request() -> usize {
+    NEXT_REQUEST.fetch_add(1, Ordering::Relaxed)
+}
+
 #[derive(Debug, Clone)]
 enum ActiveWidget {
     Location,
     Threads,
+    Members,
     Subgroups,
+    Description,
     Account,
     User,
     //Bio,
     Messages,
+    Title,
 }
 
 #[derive(Debug, Clone)]
 pub struct ThreadPage {
     location_widget: LocationWidget,
+    title_widget: TitleWidget,
     messages_widget: MessagesWidget,
     account_widget: AccountWidget,
     active: ActiveWidget,
     new: bool,
+    // the id of the opener followed by the ids of each reply
+    message_ids: Vec<String>,
+    replies: Paginator,
+    // the request loading more replies, if any
+    replies_request: Option<usize>,
     pub fetcher: APIFetcher,
     pub group_path: String,
     pub thread_id: String,
     pub username: String,
 }
 
+#[allow(clippy::needless_return)]
 fn split_line_vec(s: String) -> Vec<String> {
     let mut v = vec![String::new()];
     for chr in s.chars() {
@@ -55,75 +83,260 @@ impl ThreadPage {
         thread_id: String,
         username: String,
         new: bool,
-    ) -> ThreadPage {
+    ) -> Result<ThreadPage, BorzError> {
         let mut lw = LocationWidget::new(group_path.clone());
         lw.focus();
-        let res = fetcher
-            .query_thread_content(thread_id.clone())
-            .data
-            .unwrap()
+        let content = fetcher.query_thread_content(thread_id.clone())?;
+        let res = content
             .thread
-            .unwrap();
+            .ok_or_else(|| BorzError::NotFound(format!("thread {}", thread_id)))?;
+        let user_id = content.me.map(|me| me.id).unwrap_or_default();
+        let mut page = ThreadPage {
+            location_widget: lw,
+            title_widget: TitleWidget::new(res.title),
+            messages_widget: MessagesWidget::new(
+                vec![res.author.username],
+                vec![res.author.id],
+                vec![split_line_vec(res.content)],
+                username.clone(),
+                user_id,
+                new,
+            ),
+            account_widget: AccountWidget::new(username.clone()),
+            active: ActiveWidget::Location,
+            new,
+            message_ids: vec![res.id],
+            replies: Paginator::new(),
+            replies_request: None,
+            fetcher,
+            group_path,
+            username,
+            thread_id,
+        };
+        page.load_replies()?;
+        Ok(page)
+    }
+
+    pub fn load_replies(&mut self) -> Result<(), BorzError> {
+        let fetcher = &mut self.fetcher;
+        let thread_id = self.thread_id.clone();
+        let replies = self
+            .replies
+            .next(|after| fetcher.query_replies(thread_id, after))?;
+        self.add_replies(replies);
+        Ok(())
+    }
+
+    // fetches the next page of replies on a worker thread
+    pub fn load_more_replies(&mut self) -> Option<Job> {
+        if self.replies_request.is_some() || !self.replies.has_next_page() {
+            return None;
+        }
+        let request = next_request();
+        self.replies_request = Some(request);
+        let mut fetcher = self.fetcher.clone();
+        let mut replies = self.replies.clone();
+        let thread_id = self.thread_id.clone();
+        Some(Box::new(move || {
+            let tid = thread_id.clone();
+            let res = replies.next(|after| fetcher.query_replies(tid, after));
+            Box::new(move |app: &mut App| {
+                if let Some(tp) = app.find_thread(&thread_id) {
+                    if tp.replies_request != Some(request) {
+                        return;
+                    }
+                    tp.replies_request = None;
+                    match res {
+                        Ok(nodes) => {
+                            tp.replies = replies;
+                            tp.add_replies(nodes);
+                        }
+                        Err(e) => app.show_error(e),
+                    }
+                }
+            })
+        }))
+    }
+
+    fn add_replies(&mut self, replies: Vec<ReplyNode>) {
         let mut authors = vec![];
-        let mut content: Vec<Vec<String>> = vec![];
-        authors.push(res.author.username);
-        content.push(split_line_vec(res.content));
-        for n in res.replies.edges {
-            let node = n.unwrap().node.unwrap();
+        let mut author_ids = vec![];
+        let mut content = vec![];
+        for node in replies {
+            self.message_ids.push(node.id);
             authors.push(node.author.username);
+            author_ids.push(node.author.id);
             content.push(split_line_vec(node.content));
         }
+        self.messages_widget
+            .extend(authors, author_ids, content, self.replies.has_next_page());
+    }
+
+    // creates an empty page for composing a new thread in the fetcher's subgroup
+    pub fn compose(fetcher: APIFetcher, group_path: String, username: String) -> ThreadPage {
+        let mut tw = TitleWidget::new(String::new());
+        tw.focus();
         ThreadPage {
-            location_widget: lw,
-            messages_widget: MessagesWidget::new(authors, content, username.clone(), new),
+            location_widget: LocationWidget::new(group_path.clone()),
+            title_widget: tw,
+            messages_widget: MessagesWidget::new(
+                vec![],
+                vec![],
+                vec![],
+                username.clone(),
+                String::new(),
+                true,
+            ),
             account_widget: AccountWidget::new(username.clone()),
-            active: ActiveWidget::Location,
-            new,
+            active: ActiveWidget::Title,
+            new: true,
+            message_ids: vec![],
+            replies: Paginator::new(),
+            replies_request: None,
             fetcher,
             group_path,
             username,
-            thread_id,
+            thread_id: String::new(),
+        }
+    }
+
+    pub fn reload(&self) -> Result<ThreadPage, BorzError> {
+        let mut page = ThreadPage::new(
+            self.fetcher.clone(),
+            self.group_path.clone(),
+            self.thread_id.clone(),
+            self.username.clone(),
+            false,
+        )?;
+        // load at least as many messages as before so a new reply is visible
+        while page.replies.has_next_page() && page.message_ids.len() <= self.message_ids.len() {
+            page.load_replies()?;
+        }
+        Ok(page)
+    }
+
+    pub fn reply(&mut self, content: String) -> Result<ThreadPage, BorzError> {
+        self.fetcher
+            .mutate_thread_reply(self.thread_id.clone(), content)?;
+        self.reload()
+    }
+
+    // the opener is message 0 and is updated together with the thread title
+    pub fn update_message(
+        &mut self,
+        index: usize,
+        content: String,
+    ) -> Result<ThreadPage, BorzError> {
+        if index == 0 {
+            self.fetcher.mutate_update_thread(
+                self.thread_id.clone(),
+                self.title_widget.get_title(),
+                content,
+            )?;
+        } else {
+            self.fetcher
+                .mutate_update_reply(self.message_ids[index].clone(), content)?;
         }
+        let mut page = self.reload()?;
+        // the reply being written before the edit carries over to the reloaded page
+        page.messages_widget
+            .restore_draft(self.messages_widget.take_draft());
+        Ok(page)
     }
 
+    pub fn create_thread(&mut self, content: String) -> Result<ThreadPage, BorzError> {
+        let title = self.title_widget.get_title();
+        if title.is_empty() {
+            return Err(BorzError::InvalidInput(String::from(
+                "Please enter a title for the thread",
+            )));
+        }
+        let thread = self
+            .fetcher
+            .mutate_create_thread(title, content)?
+            .create_thread
+            .and_then(|payload| payload.thread)
+            .ok_or(BorzError::MissingData("thread"))?;
+        ThreadPage::new(
+            self.fetcher.clone(),
+            self.group_path.clone() + "/" + &thread.title[..],
+            thread.id,
+            self.username.clone(),
+            false,
+        )
+    }
+
+    #[allow(clippy::needless_return)]
     pub fn update(&mut self, key: KeyEvent) -> Box<dyn for<'a> Fn(&'a mut App)> {
+        // while typing a message tab indents and shift-tab moves between widgets
+        let typing =
+            matches!(self.active, ActiveWidget::Messages) && self.messages_widget.is_editing();
+        let cycle = match key.code {
+            KeyCode::BackTab => true,
+            KeyCode::Tab => key.modifiers.is_empty() && !typing,
+            _ => false,
+        };
+        if cycle {
+            match self.active {
+                ActiveWidget::Location => {
+                    self.location_widget.unfocus();
+                    self.messages_widget.focus();
+                    self.active = ActiveWidget::Messages;
+                }
+                ActiveWidget::Title => {
+                    self.title_widget.unfocus();
+                    self.messages_widget.focus();
+                    self.active = ActiveWidget::Messages;
+                }
+                ActiveWidget::Messages => {
+                    self.messages_widget.unfocus();
+                    self.account_widget.focus();
+                    self.active = ActiveWidget::Account;
+                }
+                ActiveWidget::Account if self.new => {
+                    self.account_widget.unfocus();
+                    self.title_widget.focus();
+                    self.active = ActiveWidget::Title;
+                }
+                ActiveWidget::Account => {
+                    self.account_widget.unfocus();
+                    self.location_widget.focus();
+                    self.active = ActiveWidget::Location;
+                }
+                _ => panic!("Illegal active widget"),
+            };
+            return Box::new(|_| {});
+        }
         if key.modifiers.is_empty() {
-            if let KeyCode::Tab = key.code {
-                match self.active {
-                    ActiveWidget::Location => {
-                        self.location_widget.unfocus();
-                        self.messages_widget.focus();
-                        self.active = ActiveWidget::Messages;
-                    }
-                    ActiveWidget::Messages => {
-                        self.messages_widget.unfocus();
-                        self.account_widget.focus();
-                        self.active = ActiveWidget::Account;
-                    }
-                    ActiveWidget::Account => {
-                        self.account_widget.unfocus();
-                        self.location_widget.focus();
-                        self.active = ActiveWidget::Location;
-                    }
-                    _ => panic!("Illegal active widget"),
-                };
-                return Box::new(|_| {});
-            } else if let KeyCode::Esc = key.code {
-                return Box::new(|app| {
-                    app.pop_page().unwrap();
-                });
+            if let KeyCode::Esc = key.code {
+                // escape leaves a reply or an edit before it leaves the page
+                if !typing || self.new {
+                    return Box::new(|app| {
+                        app.pop_page().unwrap();
+                    });
+                }
             }
         }
         return match self.active {
             ActiveWidget::Location => self.location_widget.update(key),
+            ActiveWidget::Title => self.title_widget.update(key),
             ActiveWidget::Messages => self.messages_widget.update(key),
             ActiveWidget::Account => self.account_widget.update(key),
             _ => panic!("Illegal active widget"),
         };
     }
 
-    pub fn resize(&mut self, area: &Rect) {
-        let chunks = Layout::default()
+    // pasted text goes to whichever text field is active
+    pub fn paste(&mut self, text: &str) {
+        match self.active {
+            ActiveWidget::Title => self.title_widget.paste(text),
+            ActiveWidget::Messages => self.messages_widget.paste(text),
+            _ => {}
+        }
+    }
+
+    fn layout(&self, area: Rect) -> Vec<Rect> {
+        Layout::default()
             .direction(Direction::Vertical)
             .margin(1)
             .constraints(
@@ -134,26 +347,24 @@ impl ThreadPage {
                 ]
                 .as_ref(),
             )
-            .split(area.clone());
+            .split(area)
+    }
+
+    pub fn resize(&mut self, area: &Rect) {
+        let chunks = self.layout(*area);
+        self.title_widget.resize(&chunks[0]);
         self.messages_widget.resize(&chunks[1]);
     }
 }
 
 impl Widget for ThreadPage {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .margin(1)
-            .constraints(
-                [
-                    Constraint::Percentage(10),
-                    Constraint::Percentage(80),
-                    Constraint::Percentage(10),
-                ]
-                .as_ref(),
-            )
-            .split(area);
-        self.location_widget.render(chunks[0], buf);
+        let chunks = self.layout(area);
+        if self.new {
+            self.title_widget.render(chunks[0], buf);
+        } else {
+            self.location_widget.render(chunks[0], buf);
+        }
         self.messages_widget.render(chunks[1], buf);
         self.account_widget.render(chunks[2], buf);
     }
@@ -163,55 +374,346 @@ impl Widget for ThreadPage {
 pub struct GroupPage {
     location_widget: LocationWidget,
     threads_widget: ThreadsWidget,
+    members_widget: MembersWidget,
     subgroups_widget: SubgroupsWidget,
+    description_widget: DescriptionWidget,
     account_widget: AccountWidget,
     active: ActiveWidget,
+    // shown over the page while a subgroup is being created
+    dialog: Option<Box<NewSubgroupWidget>>,
+    // members are listed in place of the threads
+    show_members: bool,
+    threads: Paginator,
+    subgroups: Paginator,
+    members: Paginator,
+    // the requests loading more of each list, if any; results for an old members filter are dropped too
+    threads_request: Option<usize>,
+    subgroups_request: Option<usize>,
+    members_request: Option<usize>,
+    // ids of the subgroups the user has joined
+    memberships: HashSet<String>,
     pub fetcher: APIFetcher,
     pub group_path: String,
     pub username: String,
 }
 
 impl GroupPage {
-    pub fn new(mut fetcher: APIFetcher, group_path: String, username: String) -> GroupPage {
+    pub fn new(
+        fetcher: APIFetcher,
+        group_path: String,
+        username: String,
+    ) -> Result<GroupPage, BorzError> {
         let mut gw = LocationWidget::new(group_path.clone());
         gw.focus();
-        let subgroups = fetcher.query_subgroups();
+        let description = fetcher.query_subgroup()?.description;
+        let mut page = GroupPage {
+            location_widget: gw,
+            threads_widget: ThreadsWidget::new(vec![], vec![], vec![], vec![]),
+            members_widget: MembersWidget::new(vec![], vec![]),
+            subgroups_widget: SubgroupsWidget::new(group_path.clone(), vec![], vec![], vec![]),
+            description_widget: DescriptionWidget::new(description),
+            account_widget: AccountWidget::new(username.clone()),
+            active: ActiveWidget::Location,
+            dialog: None,
+            show_members: false,
+            threads: Paginator::new(),
+            subgroups: Paginator::new(),
+            members: Paginator::new(),
+            threads_request: None,
+            subgroups_request: None,
+            members_request: None,
+            memberships: HashSet::new(),
+            fetcher,
+            username,
+            group_path,
+        };
+        page.load_memberships()?;
+        page.load_subgroups()?;
+        page.load_threads()?;
+        Ok(page)
+    }
+
+    fn load_memberships(&mut self) -> Result<(), BorzError> {
+        let fetcher = &self.fetcher;
+        let memberships = Paginator::new().collect(|after| fetcher.query_memberships(after))?;
+        self.memberships = memberships.into_iter().map(|node| node.id).collect();
+        let member = self.memberships.contains(self.fetcher.node_id());
+        self.location_widget.set_member(member);
+        Ok(())
+    }
+
+    // joins the subgroup if the user is not a member yet and leaves it otherwise
+    pub fn toggle_membership(&mut self, id: String) -> Job {
+        let member = self.memberships.contains(&id);
+        let fetcher = self.fetcher.clone();
+        let group_path = self.group_path.clone();
+        Box::new(move || {
+            let res = if member {
+                fetcher.mutate_leave_subgroup(id.clone())
+            } else {
+                fetcher.mutate_join_subgroup(id.clone())
+            };
+            Box::new(move |app: &mut App| match res {
+                Ok(()) => {
+                    if let Some(gp) = app.find_group(&group_path) {
+                        gp.set_membership(&id, !member);
+                    }
+                }
+                Err(e) => app.show_error(e),
+            })
+        })
+    }
+
+    pub fn save_description(&mut self, description: String) -> Job {
+        let fetcher = self.fetcher.clone();
+        let group_path = self.group_path.clone();
+        Box::new(move || {
+            let res = fetcher.mutate_update_subgroup(description.clone());
+            Box::new(move |app: &mut App| match res {
+                Ok(()) => {
+                    if let Some(gp) = app.find_group(&group_path) {
+                        gp.description_widget.set_description(description);
+                    }
+                }
+                Err(e) => app.show_error(e),
+            })
+        })
+    }
+
+    pub fn close_dialog(&mut self) {
+        self.dialog = None;
+    }
+
+    // creates the subgroup described in the dialog, which stays open if the server refuses it
+    pub fn create_subgroup(&mut self) -> Result<Job, BorzError> {
+        let dialog = self
+            .dialog
+            .as_ref()
+            .ok_or(BorzError::MissingData("dialog"))?;
+        let name = dialog.name();
+        let slug = dialog.slug();
+        let description = dialog.description();
+        if name.is_empty() {
+            return Err(BorzError::InvalidInput(String::from(
+                "Please enter a name for the subgroup",
+            )));
+        }
+        if slug.is_empty() {
+            return Err(BorzError::InvalidInput(String::from(
+                "Please enter a slug for the subgroup, as none could be made from its name",
+            )));
+        }
+        let fetcher = self.fetcher.clone();
+        let group_path = self.group_path.clone();
+        Ok(Box::new(move || {
+            let res = fetcher.mutate_create_subgroup(name.clone(), slug, description);
+            Box::new(move |app: &mut App| match res {
+                Ok(id) => {
+                    if let Some(gp) = app.find_group(&group_path) {
+                        // until every page is loaded the new subgroup arrives with the last one
+                        if !gp.subgroups.has_next_page() {
+                            gp.subgroups_widget
+                                .extend(vec![name], vec![id], vec![false]);
+                        }
+                        gp.close_dialog();
+                    }
+                }
+                Err(e) => app.show_error(e),
+            })
+        }))
+    }
+
+    fn set_membership(&mut self, id: &str, member: bool) {
+        if member {
+            self.memberships.insert(String::from(id));
+        } else {
+            self.memberships.remove(id);
+        }
+        self.subgroups_widget.set_member(id, member);
+        if id == self.fetcher.node_id() {
+            self.location_widget.set_member(member);
+        }
+    }
+
+    pub fn load_subgroups(&mut self) -> Result<(), BorzError> {
+        let fetcher = &mut self.fetcher;
+        let subgroups = self
+            .subgroups
+            .next(|after| fetcher.query_subgroups(after))?;
+        self.add_subgroups(subgroups);
+        Ok(())
+    }
+
+    pub fn load_threads(&mut self) -> Result<(), BorzError> {
+        let fetcher = &mut self.fetcher;
+        let threads = self.threads.next(|after| fetcher.query_threads(after))?;
+        self.add_threads(threads);
+        Ok(())
+    }
+
+    // fetches the next page of subgroups on a worker thread
+    pub fn load_more_subgroups(&mut self) -> Option<Job> {
+        if self.subgroups_request.is_some() || !self.subgroups.has_next_page() {
+            return None;
+        }
+        let request = next_request();
+        self.subgroups_request = Some(request);
+        let mut fetcher = self.fetcher.clone();
+        let mut subgroups = self.subgroups.clone();
+        let group_path = self.group_path.clone();
+        Some(Box::new(move || {
+            let res = subgroups.next(|after| fetcher.query_subgroups(after));
+            Box::new(move |app: &mut App| {
+                if let Some(gp) = app.find_group(&group_path) {
+                    if gp.subgroups_request != Some(request) {
+                        return;
+                    }
+                    gp.subgroups_request = None;
+                    match res {
+                        Ok(nodes) => {
+                            gp.subgroups = subgroups;
+                            gp.add_subgroups(nodes);
+                        }
+                        Err(e) => app.show_error(e),
+                    }
+                }
+            })
+        }))
+    }
+
+    // fetches the next page of threads on a worker thread
+    pub fn load_more_threads(&mut self) -> Option<Job> {
+        if self.threads_request.is_some() || !self.threads.has_next_page() {
+            return None;
+        }
+        let request = next_request();
+        self.threads_request = Some(request);
+        let mut fetcher = self.fetcher.clone();
+        let mut threads = self.threads.clone();
+        let group_path = self.group_path.clone();
+        Some(Box::new(move || {
+            let res = threads.next(|after| fetcher.query_threads(after));
+            Box::new(move |app: &mut App| {
+                if let Some(gp) = app.find_group(&group_path) {
+                    if gp.threads_request != Some(request) {
+                        return;
+                    }
+                    gp.threads_request = None;
+                    match res {
+                        Ok(nodes) => {
+                            gp.threads = threads;
+                            gp.add_threads(nodes);
+                        }
+                        Err(e) => app.show_error(e),
+                    }
+                }
+            })
+        }))
+    }
+
+    // lists the members whose username contains the filter from the first page
+    pub fn filter_members(&mut self, filter: String) -> Job {
+        self.members = Paginator::new();
+        self.members_request = None;
+        self.members_widget.set_filter(filter);
+        self.load_more_members().unwrap()
+    }
+
+    // fetches the next page of members on a worker thread
+    pub fn load_more_members(&mut self) -> Option<Job> {
+        if self.members_request.is_some() || !self.members.has_next_page() {
+            return None;
+        }
+        let request = next_request();
+        self.members_request = Some(request);
+        let fetcher = self.fetcher.clone();
+        let mut members = self.members.clone();
+        let filter = self.members_widget.filter();
+        let group_path = self.group_path.clone();
+        Some(Box::new(move || {
+            let username = if filter.is_empty() {
+                None
+            } else {
+                Some(filter)
+            };
+            let res = members.next(|after| fetcher.query_members(username, after));
+            Box::new(move |app: &mut App| {
+                if let Some(gp) = app.find_group(&group_path) {
+                    if gp.members_request != Some(request) {
+                        return;
+                    }
+                    gp.members_request = None;
+                    match res {
+                        Ok(nodes) => {
+                            gp.members = members;
+                            gp.add_members(nodes);
+                        }
+                        Err(e) => app.show_error(e),
+                    }
+                }
+            })
+        }))
+    }
+
+    fn add_members(&mut self, members: Vec<MemberNode>) {
+        let mut username_list = vec![];
+        let mut uid_list = vec![];
+        for node in members {
+            username_list.push(node.username);
+            uid_list.push(node.id);
+        }
+        self.members_widget.extend(username_list, uid_list);
+    }
+
+    fn add_subgroups(&mut self, subgroups: Vec<SubgroupNode>) {
         let mut sb_list = vec![];
         let mut sbid_list = vec![];
-        for subgroup in subgroups.data.unwrap().subgroup.unwrap().child_group.edges {
-            let node = subgroup.unwrap().node.unwrap();
+        let mut member_list = vec![];
+        for node in subgroups {
+            member_list.push(self.memberships.contains(&node.id));
             sb_list.push(node.name);
             sbid_list.push(node.id);
         }
-        let threads = fetcher.query_threads();
+        self.subgroups_widget
+            .extend(sb_list, sbid_list, member_list);
+    }
+
+    fn add_threads(&mut self, threads: Vec<ThreadNode>) {
         let mut title_list = vec![];
         let mut tid_list = vec![];
         let mut author_list = vec![];
         let mut aid_list = vec![];
-        for thread in threads.data.unwrap().subgroup.unwrap().threads.edges {
-            let node = thread.unwrap().node.unwrap();
+        for node in threads {
             title_list.push(node.title);
             tid_list.push(node.id);
             let aut = node.author;
             author_list.push(aut.username);
             aid_list.push(aut.id);
         }
-        GroupPage {
-            location_widget: gw,
-            threads_widget: ThreadsWidget::new(title_list, tid_list, author_list, aid_list),
-            subgroups_widget: SubgroupsWidget::new(group_path.clone(), sb_list, sbid_list),
-            account_widget: AccountWidget::new(username.clone()),
-            active: ActiveWidget::Location,
-            fetcher,
-            username,
-            group_path,
-        }
+        self.threads_widget
+            .extend(title_list, tid_list, author_list, aid_list);
     }
 
+    #[allow(clippy::needless_return)]
     pub fn update(&mut self, key: KeyEvent) -> Box<dyn for<'a> Fn(&'a mut App)> {
+        // text entry takes every key until it is finished
+        if let Some(dialog) = &mut self.dialog {
+            return dialog.update(key);
+        }
+        if self.description_widget.is_editing() {
+            return self.description_widget.update(key);
+        }
+        if self.members_widget.is_filtering() {
+            return self.members_widget.update(key);
+        }
         if key.modifiers.is_empty() {
             if let KeyCode::Tab = key.code {
                 match self.active {
+                    ActiveWidget::Location if self.show_members => {
+                        self.location_widget.unfocus();
+                        self.members_widget.focus();
+                        self.active = ActiveWidget::Members;
+                    }
                     ActiveWidget::Location => {
                         self.location_widget.unfocus();
                         self.threads_widget.focus();
@@ -222,8 +724,18 @@ impl GroupPage {
                         self.subgroups_widget.focus();
                         self.active = ActiveWidget::Subgroups;
                     }
+                    ActiveWidget::Members => {
+                        self.members_widget.unfocus();
+                        self.subgroups_widget.focus();
+                        self.active = ActiveWidget::Subgroups;
+                    }
                     ActiveWidget::Subgroups => {
                         self.subgroups_widget.unfocus();
+                        self.description_widget.focus();
+                        self.active = ActiveWidget::Description;
+                    }
+                    ActiveWidget::Description => {
+                        self.description_widget.unfocus();
                         self.account_widget.focus();
                         self.active = ActiveWidget::Account;
                     }
@@ -239,16 +751,63 @@ impl GroupPage {
                 return Box::new(|app| {
                     app.pop_page().unwrap();
                 });
+            } else if let KeyCode::Char('m') = key.code {
+                // swaps the threads for the members of the group, keeping the focus in place
+                self.show_members = !self.show_members;
+                match self.active {
+                    ActiveWidget::Threads => {
+                        self.threads_widget.unfocus();
+                        self.members_widget.focus();
+                        self.active = ActiveWidget::Members;
+                    }
+                    ActiveWidget::Members => {
+                        self.members_widget.unfocus();
+                        self.threads_widget.focus();
+                        self.active = ActiveWidget::Threads;
+                    }
+                    _ => {}
+                }
+                // the first page of members is loaded the first time they are shown
+                let fresh = self.members.has_next_page() && self.members.end_cursor().is_none();
+                if self.show_members && fresh {
+                    return Box::new(|app| {
+                        if let AppPage::Group(gp) = app.get_page().unwrap() {
+                            if let Some(job) = gp.load_more_members() {
+                                app.spawn(job);
+                            }
+                        } else {
+                            panic!("Wrong page execution");
+                        }
+                    });
+                }
+                return Box::new(|_| {});
+            } else if let KeyCode::Char('n') = key.code {
+                self.dialog = Some(Box::new(NewSubgroupWidget::new(self.group_path.clone())));
+                return Box::new(|_| {});
+            } else if let KeyCode::Char('j') = key.code {
+                // toggles the highlighted subgroup, or the current group from any other widget
+                let id = match self.active {
+                    ActiveWidget::Subgroups => self.subgroups_widget.selected_id(),
+                    _ => None,
+                }
+                .unwrap_or_else(|| String::from(self.fetcher.node_id()));
+                return Box::new(move |app| {
+                    if let AppPage::Group(gp) = app.get_page().unwrap() {
+                        let job = gp.toggle_membership(id.clone());
+                        app.spawn(job);
+                    } else {
+                        panic!("Wrong page execution");
+                    }
+                });
             } else if let KeyCode::Home = key.code {
                 return Box::new(|app| {
-                    if let AppPage::Group(_gp) = app.get_page().unwrap() {
-                        /* Creating a new thread is not yet implemented
-                        app.push_page(AppPage::Thread(ThreadPage::new(
+                    if let AppPage::Group(gp) = app.get_page().unwrap() {
+                        let gp = gp.clone();
+                        app.push_page(AppPage::Thread(Box::new(ThreadPage::compose(
                             gp.fetcher.clone(),
-                            gp.group_path + "/Untitled",
-
-                        )));
-                        */
+                            gp.group_path.clone(),
+                            gp.username.clone(),
+                        ))));
                     } else {
                         panic!("Wrong page execution");
                     }
@@ -258,50 +817,69 @@ impl GroupPage {
         return match self.active {
             ActiveWidget::Location => self.location_widget.update(key),
             ActiveWidget::Threads => self.threads_widget.update(key),
+            ActiveWidget::Members => self.members_widget.update(key),
             ActiveWidget::Subgroups => self.subgroups_widget.update(key),
+            ActiveWidget::Description => self.description_widget.update(key),
             ActiveWidget::Account => self.account_widget.update(key),
             _ => panic!("Illegal active widget"),
         };
     }
 
-    pub fn resize(&mut self, area: &Rect) {
-        let chunks = Layout::default()
+    // pasted text goes to whichever text field is being typed in
+    pub fn paste(&mut self, text: &str) {
+        if let Some(dialog) = &mut self.dialog {
+            dialog.paste(text);
+        } else if self.description_widget.is_editing() {
+            self.description_widget.paste(text);
+        } else if self.members_widget.is_filtering() {
+            self.members_widget.paste(text);
+        }
+    }
+
+    fn layout(&self, area: Rect) -> Vec<Rect> {
+        Layout::default()
             .direction(Direction::Vertical)
             .margin(1)
             .constraints(
                 [
                     Constraint::Percentage(10),
-                    Constraint::Percentage(60),
+                    Constraint::Percentage(45),
                     Constraint::Percentage(20),
+                    Constraint::Percentage(15),
                     Constraint::Percentage(10),
                 ]
                 .as_ref(),
             )
-            .split(area.clone());
+            .split(area)
+    }
+
+    pub fn resize(&mut self, area: &Rect) {
+        let chunks = self.layout(*area);
         self.threads_widget.resize(&chunks[1]);
+        self.members_widget.resize(&chunks[1]);
         self.subgroups_widget.resize(&chunks[2]);
+        self.description_widget.resize(&chunks[3]);
+        if let Some(dialog) = &mut self.dialog {
+            dialog.resize(&popup_area(*area));
+        }
     }
 }
 
 impl Widget for GroupPage {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .margin(1)
-            .constraints(
-                [
-                    Constraint::Percentage(10),
-                    Constraint::Percentage(60),
-                    Constraint::Percentage(20),
-                    Constraint::Percentage(10),
-                ]
-                .as_ref(),
-            )
-            .split(area);
+        let chunks = self.layout(area);
         self.location_widget.render(chunks[0], buf);
-        self.threads_widget.render(chunks[1], buf);
+        if self.show_members {
+            self.members_widget.render(chunks[1], buf);
+        } else {
+            self.threads_widget.render(chunks[1], buf);
+        }
         self.subgroups_widget.render(chunks[2], buf);
-        self.account_widget.render(chunks[3], buf);
+        self.description_widget.render(chunks[3], buf);
+        self.account_widget.render(chunks[4], buf);
+        if let Some(dialog) = self.dialog {
+            dialog.render(popup_area(area), buf);
+        }
     }
 }
 
@@ -310,24 +888,33 @@ pub struct UserPage {
     user_widget: UserWidget,
     //bio_widget: BioWidget,
     active: ActiveWidget,
+    // although this field is never read, it may be used in the future
+    #[allow(dead_code)]
     pub fetcher: APIFetcher,
 }
 
 impl UserPage {
-    pub fn new(mut fetcher: APIFetcher, user_id: String) -> UserPage {
-        let res = fetcher.query_user(user_id).data.unwrap().user.unwrap();
+    pub fn new(mut fetcher: APIFetcher, user_id: String) -> Result<UserPage, BorzError> {
+        let res = fetcher
+            .query_user(user_id.clone())?
+            .user
+            .ok_or_else(|| BorzError::NotFound(format!("user {}", user_id)))?;
         let username = res.username;
-        let joined = res.date_joined.unwrap().parse::<u64>().unwrap();
+        let joined = res
+            .date_joined
+            .and_then(|d| d.parse::<u64>().ok())
+            .ok_or(BorzError::MissingData("dateJoined"))?;
         let mut uw = UserWidget::new(username, joined);
         uw.focus();
-        UserPage {
+        Ok(UserPage {
             user_widget: uw,
             //bio_widget: BioWidget::new("This is a bio!".repeat(100)),
             active: ActiveWidget::User,
             fetcher,
-        }
+        })
     }
 
+    #[allow(clippy::needless_return)]
     pub fn update(&mut self, key: KeyEvent) -> Box<dyn for<'a> Fn(&'a mut App)> {
         if key.modifiers.is_empty() {
             if let KeyCode::Esc = key.code {
@@ -375,3 +962,58 @@ impl Widget for UserPage {
         //self.bio_widget.render(chunks[1], buf);
     }
 }
+
+#[derive(Debug, Clone)]
+pub struct LoadingPage {
+    pub id: usize,
+    pub replace: bool,
+    title: String,
+    frame: usize,
+}
+
+impl LoadingPage {
+    pub fn new(id: usize, title: String, replace: bool) -> LoadingPage {
+        LoadingPage {
+            id,
+            replace,
+            title,
+            frame: 0,
+        }
+    }
+
+    pub fn tick(&mut self) {
+        self.frame = (self.frame + 1) % 4;
+    }
+
+    pub fn update(&mut self, key: KeyEvent) -> Box<dyn for<'a> Fn(&'a mut App)> {
+        if key.modifiers.is_empty() {
+            if let KeyCode::Esc = key.code {
+                return Box::new(|app| {
+                    app.pop_page().unwrap();
+                });
+            }
+        }
+        Box::new(|_| {})
+    }
+
+    pub fn resize(&mut self, _area: &Rect) {}
+}
+
+impl Widget for LoadingPage {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints([Constraint::Percentage(10), Constraint::Percentage(90)].as_ref())
+            .split(area);
+        let paragraph = Paragraph::new(format!("{}{}", self.title, ".".repeat(self.frame)))
+            .block(
+                Block::default()
+                    .title("Loading")
+                    .borders(Borders::ALL)
+                    .style(Style::default().bg(Color::Green).fg(Color::White)),
+            )
+            .wrap(Wrap { trim: true });
+        paragraph.render(chunks[0], buf);
+    }
+}
@@ -1,5 +1,7 @@
+use crate::api::paginate::LOAD_AHEAD;
 use crate::app::{App, AppPage};
 use crate::widgets::page::GroupPage;
+use crate::widgets::text::truncate_start;
 use crossterm::event::{KeyCode, KeyEvent};
 use std::cmp::min;
 use tui::buffer::Buffer;
@@ -13,6 +15,7 @@ pub struct SubgroupsWidget {
     group: String,
     subgroups: Vec<String>,
     sbids: Vec<String>,
+    members: Vec<bool>,
     selected_row: usize,
     focused: bool,
     scroll_top: usize,
@@ -20,10 +23,16 @@ pub struct SubgroupsWidget {
 }
 
 impl SubgroupsWidget {
-    pub fn new(group: String, subgroups: Vec<String>, sbids: Vec<String>) -> SubgroupsWidget {
+    pub fn new(
+        group: String,
+        subgroups: Vec<String>,
+        sbids: Vec<String>,
+        members: Vec<bool>,
+    ) -> SubgroupsWidget {
         SubgroupsWidget {
             group,
             subgroups,
+            members,
             selected_row: 0,
             focused: false,
             scroll_top: 0,
@@ -32,6 +41,22 @@ impl SubgroupsWidget {
         }
     }
 
+    pub fn extend(&mut self, subgroups: Vec<String>, sbids: Vec<String>, members: Vec<bool>) {
+        self.subgroups.extend(subgroups);
+        self.sbids.extend(sbids);
+        self.members.extend(members);
+    }
+
+    pub fn selected_id(&self) -> Option<String> {
+        self.sbids.get(self.selected_row).cloned()
+    }
+
+    pub fn set_member(&mut self, id: &str, member: bool) {
+        if let Some(pos) = self.sbids.iter().position(|sbid| sbid == id) {
+            self.members[pos] = member;
+        }
+    }
+
     pub fn focus(&mut self) {
         self.focused = true;
     }
@@ -61,6 +86,7 @@ impl SubgroupsWidget {
         }
     }
 
+    #[allow(clippy::needless_return)]
     pub fn update(&mut self, key: KeyEvent) -> Box<dyn for<'a> Fn(&'a mut App)> {
         if !self.focused {
             return Box::new(|_| {});
@@ -70,6 +96,17 @@ impl SubgroupsWidget {
                 KeyCode::Down if self.selected_row < self.subgroups.len() - 1 => {
                     self.selected_row += 1;
                     self.scroll();
+                    if self.selected_row + LOAD_AHEAD >= self.subgroups.len() {
+                        return Box::new(|app| {
+                            if let AppPage::Group(gp) = app.get_page().unwrap() {
+                                if let Some(job) = gp.load_more_subgroups() {
+                                    app.spawn(job);
+                                }
+                            } else {
+                                panic!("Wrong page execution");
+                            }
+                        });
+                    }
                 }
                 KeyCode::Up if self.selected_row > 0 => {
                     self.selected_row -= 1;
@@ -80,12 +117,14 @@ impl SubgroupsWidget {
                     let sg = self.subgroups[self.selected_row].clone();
                     return Box::new(move |app| {
                         if let AppPage::Group(gp) = app.get_page().unwrap() {
-                            let mut gp = gp.clone();
-                            app.push_page(AppPage::Group(GroupPage::new(
-                                gp.fetcher.child(id.clone()),
-                                gp.group_path + "/" + &sg[..],
-                                gp.username.clone(),
-                            )));
+                            let fetcher = gp.fetcher.clone();
+                            let path = gp.group_path.clone() + "/" + &sg[..];
+                            let username = gp.username.clone();
+                            let id = id.clone();
+                            app.load_page(format!("Loading {}", path), false, move || {
+                                GroupPage::new(fetcher.child(id), path, username)
+                                    .map(|gp| AppPage::Group(Box::new(gp)))
+                            });
                         } else {
                             panic!("Wrong page execution");
                         }
@@ -102,28 +141,31 @@ impl SubgroupsWidget {
 impl Widget for SubgroupsWidget {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let mut text = vec![];
-        if self.subgroups.len() > 0 {
+        if !self.subgroups.is_empty() {
             for (pos, subgroup) in self.subgroups[self.scroll_top..self.scroll_bottom]
                 .iter()
                 .enumerate()
             {
+                // subgroups the user has joined are marked with a star, kept apart from the
+                // name so truncating a long path never cuts it off
+                let marker = if self.members[pos + self.scroll_top] {
+                    "* "
+                } else {
+                    "  "
+                };
                 let name = self.group.clone() + "/" + &subgroup[..];
-                let span = Spans::from(vec![Span::styled(
-                    {
-                        if name.len() > area.width as usize {
-                            String::from("...") + &name[(name.len() - area.width as usize + 5)..]
-                        } else {
-                            name
-                        }
-                    },
-                    {
-                        if self.focused && pos + self.scroll_top == self.selected_row {
-                            Style::default().bg(Color::Red)
-                        } else {
-                            Style::default()
-                        }
-                    },
-                )]);
+                let style = if self.focused && pos + self.scroll_top == self.selected_row {
+                    Style::default().bg(Color::Red)
+                } else {
+                    Style::default()
+                };
+                let span = Spans::from(vec![
+                    Span::styled(marker, style),
+                    Span::styled(
+                        truncate_start(&name, (area.width as usize).saturating_sub(4)),
+                        style,
+                    ),
+                ]);
                 text.push(span);
             }
         }
@@ -138,7 +180,8 @@ impl Widget for SubgroupsWidget {
                         Color::White
                     })),
             )
-            .wrap(Wrap { trim: true });
+            // trimming would strip the blank marker of subgroups the user has not joined
+            .wrap(Wrap { trim: false });
         paragraph.render(area, buf);
     }
 }
@@ -0,0 +1,112 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+// the number of columns a tab advances to
+pub const TAB_WIDTH: usize = 4;
+
+// splits text into the characters a reader sees, keeping accents and emoji sequences whole
+pub fn graphemes(s: &str) -> Vec<String> {
+    s.graphemes(true).map(String::from).collect()
+}
+
+// the number of columns the text takes up on screen
+pub fn width(s: &str) -> usize {
+    UnicodeWidthStr::width(s)
+}
+
+// replaces tabs with spaces and drops carriage returns, which the terminal cannot draw in place
+pub fn expand_tabs(s: &str) -> String {
+    s.replace('\r', "").replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+// whether a typed character can be inserted as text
+pub fn is_printable(c: char) -> bool {
+    !c.is_control()
+}
+
+// pasted text for a field that holds a single line
+pub fn single_line(s: &str) -> String {
+    expand_tabs(s)
+        .chars()
+        .map(|c| if c == '\n' { ' ' } else { c })
+        .filter(|c| is_printable(*c))
+        .collect()
+}
+
+pub fn pop_grapheme(s: &mut String) {
+    if let Some((index, _)) = s.grapheme_indices(true).next_back() {
+        s.truncate(index);
+    }
+}
+
+// keeps the start of the text, ending with "..." if it is wider than the given width
+pub fn truncate(s: &str, max: usize) -> String {
+    if width(s) <= max {
+        return String::from(s);
+    }
+    let mut used = 3;
+    let mut out = String::new();
+    for g in s.graphemes(true) {
+        used += width(g);
+        if used > max {
+            break;
+        }
+        out.push_str(g);
+    }
+    out + "..."
+}
+
+// keeps the end of the text, starting with "..." if it is wider than the given width
+pub fn truncate_start(s: &str, max: usize) -> String {
+    if width(s) <= max {
+        return String::from(s);
+    }
+    let mut used = 3;
+    let mut kept = vec![];
+    for g in s.graphemes(true).rev() {
+        used += width(g);
+        if used > max {
+            break;
+        }
+        kept.push(g);
+    }
+    kept.reverse();
+    String::from("...") + &kept.concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_wide_and_combining_characters() {
+        assert_eq!(width("abc"), 3);
+        assert_eq!(width("日本"), 4);
+        assert_eq!(width("e\u{301}"), 1);
+    }
+
+    #[test]
+    fn keeps_text_that_fits() {
+        assert_eq!(truncate("hello", 5), "hello");
+        assert_eq!(truncate_start("hello", 5), "hello");
+    }
+
+    #[test]
+    fn truncates_the_end_by_width() {
+        assert_eq!(truncate("hello world", 8), "hello...");
+        assert_eq!(truncate("日本語日本語", 10), "日本語...");
+        assert_eq!(
+            truncate("e\u{301}e\u{301}e\u{301}e\u{301}e\u{301}", 4),
+            "e\u{301}..."
+        );
+    }
+
+    #[test]
+    fn truncates_the_start_by_width() {
+        assert_eq!(
+            truncate_start("/Universe/Science/Physics", 12),
+            "...e/Physics"
+        );
+        assert_eq!(truncate_start("日本語日本語", 10), "...日本語");
+    }
+}
@@ -1,5 +1,7 @@
+use crate::api::paginate::LOAD_AHEAD;
 use crate::app::{App, AppPage};
 use crate::widgets::page::{ThreadPage, UserPage};
+use crate::widgets::text::truncate;
 use crossterm::event::{KeyCode, KeyEvent};
 use std::cmp::min;
 use tui::buffer::Buffer;
@@ -41,6 +43,19 @@ impl ThreadsWidget {
         }
     }
 
+    pub fn extend(
+        &mut self,
+        threads: Vec<String>,
+        tids: Vec<String>,
+        authors: Vec<String>,
+        aids: Vec<String>,
+    ) {
+        self.threads.extend(threads);
+        self.tids.extend(tids);
+        self.authors.extend(authors);
+        self.aids.extend(aids);
+    }
+
     pub fn focus(&mut self) {
         self.focused = true;
     }
@@ -70,6 +85,7 @@ impl ThreadsWidget {
         }
     }
 
+    #[allow(clippy::needless_return)]
     pub fn update(&mut self, key: KeyEvent) -> Box<dyn for<'a> Fn(&'a mut App)> {
         if !self.focused {
             return Box::new(|_| {});
@@ -81,6 +97,17 @@ impl ThreadsWidget {
                 {
                     self.selected_row += 1;
                     self.scroll();
+                    if self.selected_row + LOAD_AHEAD >= self.threads.len() {
+                        return Box::new(|app| {
+                            if let AppPage::Group(gp) = app.get_page().unwrap() {
+                                if let Some(job) = gp.load_more_threads() {
+                                    app.spawn(job);
+                                }
+                            } else {
+                                panic!("Wrong page execution");
+                            }
+                        });
+                    }
                 }
                 KeyCode::Up if !self.authors.is_empty() && self.selected_row > 0 => {
                     self.selected_row -= 1;
@@ -98,14 +125,14 @@ impl ThreadsWidget {
                         let tid = self.tids[self.selected_row].clone();
                         return Box::new(move |app| {
                             if let AppPage::Group(gp) = app.get_page().unwrap() {
-                                let gp = gp.clone();
-                                app.push_page(AppPage::Thread(ThreadPage::new(
-                                    gp.fetcher.clone(),
-                                    gp.group_path.clone() + "/" + &pend[..],
-                                    tid.clone(),
-                                    gp.username.clone(),
-                                    false,
-                                )));
+                                let fetcher = gp.fetcher.clone();
+                                let path = gp.group_path.clone() + "/" + &pend[..];
+                                let username = gp.username.clone();
+                                let tid = tid.clone();
+                                app.load_page(format!("Loading {}", path), false, move || {
+                                    ThreadPage::new(fetcher, path, tid, username, false)
+                                        .map(|tp| AppPage::Thread(Box::new(tp)))
+                                });
                             } else {
                                 panic!("Wrong page execution");
                             }
@@ -114,11 +141,11 @@ impl ThreadsWidget {
                         let uid = self.aids[self.selected_row].clone();
                         return Box::new(move |app| {
                             if let AppPage::Group(gp) = app.get_page().unwrap() {
-                                let gp = gp.clone();
-                                app.push_page(AppPage::User(UserPage::new(
-                                    gp.fetcher.clone(),
-                                    uid.clone(),
-                                )));
+                                let fetcher = gp.fetcher.clone();
+                                let uid = uid.clone();
+                                app.load_page(String::from("Loading user"), false, move || {
+                                    UserPage::new(fetcher, uid).map(AppPage::User)
+                                });
                             } else {
                                 panic!("Wrong page execution");
                             }
@@ -147,13 +174,7 @@ impl Widget for ThreadsWidget {
                 .enumerate()
             {
                 let span = Spans::from(vec![Span::styled(
-                    {
-                        if thread.len() > area.width as usize {
-                            String::from(&thread[..(chunks[0].width as usize - 5)]) + "..."
-                        } else {
-                            thread.clone()
-                        }
-                    },
+                    truncate(thread, chunks[0].width as usize - 2),
                     {
                         if self.focused
                             && pos + self.scroll_top == self.selected_row
@@ -184,13 +205,7 @@ impl Widget for ThreadsWidget {
                 .enumerate()
             {
                 let span = Spans::from(vec![Span::styled(
-                    {
-                        if author.len() > area.width as usize {
-                            String::from(&author[..(chunks[1].width as usize - 5)]) + "..."
-                        } else {
-                            author.clone()
-                        }
-                    },
+                    truncate(author, chunks[1].width as usize - 2),
                     {
                         if self.focused
                             && pos + self.scroll_top == self.selected_row
@@ -0,0 +1,70 @@
+use crate::app::App;
+use crate::widgets::editor::Editor;
+use crossterm::event::KeyEvent;
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::style::{Color, Style};
+use tui::widgets::{Block, Borders, Paragraph, Widget, Wrap};
+
+#[derive(Debug, Clone)]
+pub struct TitleWidget {
+    editor: Editor,
+    focused: bool,
+}
+
+impl TitleWidget {
+    pub fn new(title: String) -> TitleWidget {
+        TitleWidget {
+            editor: Editor::one_line(&title),
+            focused: false,
+        }
+    }
+
+    pub fn focus(&mut self) {
+        self.focused = true;
+    }
+
+    pub fn unfocus(&mut self) {
+        self.focused = false;
+    }
+
+    pub fn get_title(&self) -> String {
+        String::from(self.editor.text().trim())
+    }
+
+    pub fn paste(&mut self, text: &str) {
+        if self.focused {
+            self.editor.paste(text);
+        }
+    }
+
+    pub fn resize(&mut self, area: &Rect) {
+        self.editor.resize(
+            (area.width as usize).saturating_sub(2),
+            (area.height as usize).saturating_sub(2),
+        );
+    }
+
+    pub fn update(&mut self, key: KeyEvent) -> Box<dyn for<'a> Fn(&'a mut App)> {
+        if self.focused {
+            self.editor.update(key);
+        }
+        Box::new(|_| {})
+    }
+}
+
+impl Widget for TitleWidget {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let paragraph = Paragraph::new(self.editor.spans(self.focused))
+            .block(Block::default().title("Title").borders(Borders::ALL).style(
+                Style::default().bg(Color::Green).fg(if self.focused {
+                    Color::Cyan
+                } else {
+                    Color::White
+                }),
+            ))
+            // the editor wraps its own rows and keeps their leading spaces
+            .wrap(Wrap { trim: false });
+        paragraph.render(area, buf);
+    }
+}
@@ -32,12 +32,14 @@ impl UserWidget {
         self.focused = false;
     }
 
+    #[allow(clippy::needless_return)]
     pub fn update(&mut self, _key: KeyEvent) -> Box<dyn for<'a> Fn(&'a mut App)> {
         return Box::new(|_| {});
     }
 }
 
 impl Widget for UserWidget {
+    #[allow(clippy::vec_init_then_push)]
     fn render(self, area: Rect, buf: &mut Buffer) {
         let mut content = vec![];
         content.push(Spans::from(vec![